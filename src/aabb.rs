@@ -0,0 +1,128 @@
+use crate::{
+    interval::Interval,
+    ray::{Point3, Ray},
+    vec3::Vec3,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Aabb::EMPTY
+    }
+}
+
+impl Aabb {
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        let mut bbox = Self { x, y, z };
+        bbox.pad_to_minimums();
+        bbox
+    }
+
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        // Treat the two points a and b as extrema for the bounding box, so we don't require a
+        // particular minimum/maximum coordinate order.
+        Self::new(
+            Interval::new(a.x().min(b.x()), a.x().max(b.x())),
+            Interval::new(a.y().min(b.y()), a.y().max(b.y())),
+            Interval::new(a.z().min(b.z()), a.z().max(b.z())),
+        )
+    }
+
+    pub fn surrounding(box0: &Aabb, box1: &Aabb) -> Self {
+        Self {
+            x: Interval::surrounding(box0.x, box1.x),
+            y: Interval::surrounding(box0.y, box1.y),
+            z: Interval::surrounding(box0.z, box1.z),
+        }
+    }
+
+    pub fn axis_interval(&self, n: usize) -> Interval {
+        match n {
+            1 => self.y,
+            2 => self.z,
+            _ => self.x,
+        }
+    }
+
+    pub fn hit(&self, r: &Ray, ray_t: &Interval) -> bool {
+        let ray_orig: Point3 = r.origin();
+        let ray_dir: Vec3 = r.direction();
+        let mut t_min: f64 = ray_t.min;
+        let mut t_max: f64 = ray_t.max;
+
+        for axis in 0..3 {
+            let ax: Interval = self.axis_interval(axis);
+            let adinv: f64 = 1.0 / ray_dir.e[axis];
+
+            let t0: f64 = (ax.min - ray_orig.e[axis]) * adinv;
+            let t1: f64 = (ax.max - ray_orig.e[axis]) * adinv;
+            let (t_near, t_far) = match t0 < t1 {
+                true => (t0, t1),
+                false => (t1, t0),
+            };
+
+            t_min = t_min.max(t_near);
+            t_max = t_max.min(t_far);
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        // Returns the index of the longest axis of the bounding box.
+        match (self.x.size(), self.y.size(), self.z.size()) {
+            (x, y, z) if x > y && x > z => 0,
+            (_, y, z) if y > z => 1,
+            _ => 2,
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            0.5 * (self.x.min + self.x.max),
+            0.5 * (self.y.min + self.y.max),
+            0.5 * (self.z.min + self.z.max),
+        )
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        if dx < 0.0 || dy < 0.0 || dz < 0.0 {
+            return 0.0;
+        }
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    fn pad_to_minimums(&mut self) {
+        // Adjust the AABB so that no side is narrower than some delta, padding if necessary.
+        let delta: f64 = 0.0001;
+        if self.x.size() < delta {
+            self.x = self.x.expand(delta);
+        }
+        if self.y.size() < delta {
+            self.y = self.y.expand(delta);
+        }
+        if self.z.size() < delta {
+            self.z = self.z.expand(delta);
+        }
+    }
+
+    pub const EMPTY: Aabb = Aabb {
+        x: Interval::EMPTY,
+        y: Interval::EMPTY,
+        z: Interval::EMPTY,
+    };
+    pub const UNIVERSE: Aabb = Aabb {
+        x: Interval::UNIVERSE,
+        y: Interval::UNIVERSE,
+        z: Interval::UNIVERSE,
+    };
+}
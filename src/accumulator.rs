@@ -0,0 +1,218 @@
+use crate::{
+    color::{luminance, Color},
+    framebuffer::Framebuffer,
+    tile::Tile,
+};
+
+// State of one pixel: the filter-weighted sum of the radiance of the samples around it and the
+// sum of their weights, plus the number of samples taken in the pixel itself and the mean and sum
+// of squared deviations of their luminance (Welford's online algorithm) for estimating its noise.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PixelSamples {
+    pub sum: Color,
+    pub weight: f64,
+    pub count: u32,
+    pub luminance_mean: f64,
+    pub luminance_m2: f64,
+}
+
+impl PixelSamples {
+    // Records a sample taken in this pixel. Its radiance reaches the image through `splat`.
+    pub fn add(&mut self, radiance: Color) {
+        self.count += 1;
+        let l: f64 = luminance(radiance);
+        let delta: f64 = l - self.luminance_mean;
+        self.luminance_mean += delta / self.count as f64;
+        self.luminance_m2 += delta * (l - self.luminance_mean);
+    }
+
+    // Estimated standard error of the pixel's luminance after a gamma 2 display encoding, so
+    // that noise in dark and bright pixels is judged the way it will be seen. Infinite until
+    // there are two samples to estimate it from.
+    pub fn display_error(&self) -> f64 {
+        if self.count < 2 {
+            return f64::INFINITY;
+        }
+        let n: f64 = self.count as f64;
+        let standard_error: f64 = (self.luminance_m2 / (n - 1.0) / n).sqrt();
+        // The derivative of sqrt(L) is 1 / (2 sqrt(L)); the floor keeps black pixels finite.
+        standard_error / (2.0 * self.luminance_mean.max(1e-4).sqrt())
+    }
+
+    pub fn splat(&mut self, radiance: Color, weight: f64) {
+        self.sum += weight * radiance;
+        self.weight += weight;
+    }
+
+    // Weighted mean radiance of the samples so far; black before the first one.
+    pub fn mean(&self) -> Color {
+        match self.weight > 0.0 {
+            true => (1.0 / self.weight) * self.sum,
+            false => Color::default(),
+        }
+    }
+}
+
+// A tile's share of an accumulator while it is rendered on its own: copies of its pixels, plus
+// what its samples splat onto the pixels in a margin around it, which belong to other tiles.
+#[derive(Clone, Debug)]
+pub struct TileSamples {
+    tile: Tile,
+    pixels: Vec<PixelSamples>,
+    region: Tile,
+    margin: Vec<PixelSamples>,
+}
+
+impl TileSamples {
+    pub fn tile(&self) -> &Tile {
+        &self.tile
+    }
+
+    // Pixels are addressed in image coordinates.
+    pub fn get(&self, x: usize, y: usize) -> &PixelSamples {
+        &self.pixels[(y - self.tile.y) * self.tile.width + x - self.tile.x]
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut PixelSamples {
+        &mut self.pixels[(y - self.tile.y) * self.tile.width + x - self.tile.x]
+    }
+
+    // Adds weighted radiance to a pixel of the tile or its margin.
+    pub fn splat(&mut self, x: usize, y: usize, radiance: Color, weight: f64) {
+        match self.tile.contains(x, y) {
+            true => self.get_mut(x, y).splat(radiance, weight),
+            false => {
+                let index: usize = (y - self.region.y) * self.region.width + x - self.region.x;
+                self.margin[index].splat(radiance, weight)
+            }
+        }
+    }
+}
+
+// Per-pixel sample sums of a render in progress, row-major with the top row first. Passes of
+// samples add to it until it is resolved into a `Framebuffer` of mean radiance.
+#[derive(Clone, Debug)]
+pub struct Accumulator {
+    width: usize,
+    height: usize,
+    pixels: Vec<PixelSamples>,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![PixelSamples::default(); width * height],
+        }
+    }
+
+    // Takes over existing per-pixel state, `width * height` pixels row-major.
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<PixelSamples>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "pixel count does not match size"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> PixelSamples {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn pixels(&self) -> &[PixelSamples] {
+        &self.pixels
+    }
+
+    // Copies out a tile's pixels to render it, with an empty margin of `margin` pixels around it
+    // for samples to splat onto.
+    pub fn read_tile(&self, tile: &Tile, margin: usize) -> TileSamples {
+        let region: Tile = tile.expand(margin, self.width, self.height);
+        TileSamples {
+            tile: *tile,
+            pixels: (tile.y..tile.y + tile.height)
+                .flat_map(|y| {
+                    let start: usize = y * self.width + tile.x;
+                    self.pixels[start..start + tile.width].iter().copied()
+                })
+                .collect(),
+            region,
+            margin: vec![PixelSamples::default(); region.pixel_count()],
+        }
+    }
+
+    // Puts a rendered tile's pixels back. Their margins go in with `add_margin` once every tile
+    // of the pass is written, as they overlap neighbouring tiles.
+    pub fn write_tile(&mut self, samples: &TileSamples) {
+        let tile: &Tile = &samples.tile;
+        for (row, src) in samples.pixels.chunks(tile.width).enumerate() {
+            let start: usize = (tile.y + row) * self.width + tile.x;
+            self.pixels[start..start + tile.width].copy_from_slice(src);
+        }
+    }
+
+    pub fn add_margin(&mut self, samples: &TileSamples) {
+        let region: &Tile = &samples.region;
+        for (row, src) in samples.margin.chunks(region.width).enumerate() {
+            let start: usize = (region.y + row) * self.width + region.x;
+            for (pixel, splat) in self.pixels[start..start + region.width].iter_mut().zip(src) {
+                if splat.weight != 0.0 {
+                    pixel.sum += splat.sum;
+                    pixel.weight += splat.weight;
+                }
+            }
+        }
+    }
+
+    pub fn total_samples(&self) -> u64 {
+        self.pixels.iter().map(|p| p.count as u64).sum()
+    }
+
+    // Debug view of where the samples went: each pixel's sample count relative to `max_samples`
+    // on a black, blue, red, yellow, white ramp.
+    pub fn sample_heatmap(&self, max_samples: u32) -> Framebuffer {
+        const RAMP: [[f64; 3]; 5] = [
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.6],
+            [0.8, 0.0, 0.0],
+            [1.0, 0.9, 0.0],
+            [1.0, 1.0, 1.0],
+        ];
+        let mut image: Framebuffer = Framebuffer::new(self.width, self.height);
+        for (index, pixel) in self.pixels.iter().enumerate() {
+            let t: f64 = (pixel.count as f64 / max_samples.max(1) as f64).min(1.0);
+            let position: f64 = t * (RAMP.len() - 1) as f64;
+            let stop: usize = (position as usize).min(RAMP.len() - 2);
+            let f: f64 = position - stop as f64;
+            let [a, b] = [RAMP[stop], RAMP[stop + 1]];
+            let color: Color = Color::new(
+                a[0] + f * (b[0] - a[0]),
+                a[1] + f * (b[1] - a[1]),
+                a[2] + f * (b[2] - a[2]),
+            );
+            image.set(index % self.width, index / self.width, color);
+        }
+        image
+    }
+
+    pub fn resolve(&self) -> Framebuffer {
+        let mut image: Framebuffer = Framebuffer::new(self.width, self.height);
+        for (index, pixel) in self.pixels.iter().enumerate() {
+            image.set(index % self.width, index / self.width, pixel.mean());
+        }
+        image
+    }
+}
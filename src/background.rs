@@ -0,0 +1,184 @@
+use std::{f64::consts::PI, path::Path, sync::Arc};
+
+use crate::{
+    color::{luminance, Color},
+    distribution::Distribution2D,
+    framebuffer::Framebuffer,
+    loaders::{hdr::load_hdr, LoadError},
+    sky::Sky,
+    vec3::{unit_vector, Vec3},
+};
+
+// Radiance arriving along rays that leave the scene without hitting anything.
+#[derive(Clone, Debug)]
+pub enum Background {
+    // The same color in every direction; black for a scene lit only by its own lights.
+    Solid(Color),
+    // A vertical blend from `bottom` straight down to `top` straight up.
+    Gradient { bottom: Color, top: Color },
+    // Light captured in an equirectangular (latitude-longitude) HDR image.
+    Environment(Arc<EnvironmentMap>),
+    // Procedural daylight: a clear sky with a sun disk.
+    Sky(Sky),
+}
+
+impl Default for Background {
+    // The book's sky.
+    fn default() -> Self {
+        Background::Gradient {
+            bottom: Self::SKY_BOTTOM,
+            top: Self::SKY_TOP,
+        }
+    }
+}
+
+impl Background {
+    // Ends of the book's sky gradient: white straight down to light blue straight up.
+    pub const SKY_BOTTOM: Color = Color { e: [1.0, 1.0, 1.0] };
+    pub const SKY_TOP: Color = Color { e: [0.5, 0.7, 1.0] };
+
+    pub fn color(&self, direction: Vec3) -> Color {
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { bottom, top } => {
+                // lerp function: blendedValue = (1 − a) * startValue + a * endValue,
+                let unit_direction: Vec3 = unit_vector(direction);
+                let a = 0.5 * (unit_direction.y() + 1.0);
+                (1.0 - a) * *bottom + (a * *top)
+            }
+            Background::Environment(map) => map.radiance(direction),
+            Background::Sky(sky) => sky.radiance(direction),
+        }
+    }
+
+    // Whether the background is a light that surfaces sample directly, through `sample` and
+    // `pdf`, rather than only finding it by scattering.
+    pub fn is_sampled(&self) -> bool {
+        matches!(self, Background::Environment(_) | Background::Sky(_))
+    }
+
+    // Picks a direction towards the background's light for a pair of uniform samples, returning
+    // it with its probability density per unit solid angle.
+    pub fn sample(&self, u: (f64, f64)) -> Option<(Vec3, f64)> {
+        match self {
+            Background::Environment(map) => map.sample(u),
+            Background::Sky(sky) => sky.sample_sun(u),
+            Background::Solid(_) | Background::Gradient { .. } => None,
+        }
+    }
+
+    // Density per unit solid angle with which `sample` picks `direction`.
+    pub fn pdf(&self, direction: Vec3) -> f64 {
+        match self {
+            Background::Environment(map) => map.pdf(direction),
+            Background::Sky(sky) => sky.pdf(direction),
+            Background::Solid(_) | Background::Gradient { .. } => 0.0,
+        }
+    }
+}
+
+// An equirectangular environment image: the top row looks straight up, the bottom row straight
+// down, and the centre column towards -z before `rotation`, which turns the environment about the
+// vertical axis. `intensity` scales the radiance of every pixel.
+//
+// The map is also a light that can be sampled directly: directions are drawn in proportion to
+// pixel luminance times the solid angle the pixel covers, so small bright features such as the
+// sun are found far more often than by bouncing rays at random.
+#[derive(Clone, Debug)]
+pub struct EnvironmentMap {
+    image: Framebuffer,
+    rotation: f64, // In radians
+    intensity: f64,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    pub fn new(image: Framebuffer, rotation_degrees: f64, intensity: f64) -> Self {
+        let (width, height) = (image.width(), image.height());
+        // Rows near the poles are squeezed onto a small solid angle, by sin(theta).
+        let weights: Vec<f64> = (0..height)
+            .flat_map(|y| {
+                let sin_theta: f64 = (PI * (y as f64 + 0.5) / height as f64).sin();
+                let image: &Framebuffer = &image;
+                (0..width).map(move |x| luminance(image.get(x, y)).max(0.0) * sin_theta)
+            })
+            .collect();
+        Self {
+            distribution: Distribution2D::new(&weights, width),
+            image,
+            rotation: rotation_degrees.to_radians(),
+            intensity,
+        }
+    }
+
+    // Loads a Radiance .hdr or .pfm image as an environment map.
+    pub fn load(
+        path: impl AsRef<Path>,
+        rotation_degrees: f64,
+        intensity: f64,
+    ) -> Result<Self, LoadError> {
+        Ok(Self::new(load_hdr(path)?, rotation_degrees, intensity))
+    }
+
+    // Bilinearly filtered radiance seen along `direction`.
+    pub fn radiance(&self, direction: Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction);
+        let (width, height) = (self.image.width(), self.image.height());
+        // Pixel centres sit at half-integer coordinates; wrap around horizontally and clamp at
+        // the poles.
+        let x: f64 = u * width as f64 - 0.5;
+        let y: f64 = (v * height as f64 - 0.5).clamp(0.0, (height - 1) as f64);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let column = |x: f64| (x as i64).rem_euclid(width as i64) as usize;
+        let (left, right) = (column(x0), column(x0 + 1.0));
+        let (top, bottom) = (y0 as usize, (y0 as usize + 1).min(height - 1));
+        let row = |y: usize| (1.0 - fx) * self.image.get(left, y) + fx * self.image.get(right, y);
+        self.intensity * ((1.0 - fy) * row(top) + fy * row(bottom))
+    }
+
+    // Picks a direction towards the map for a pair of uniform samples, returning it with its
+    // probability density per unit solid angle. None where the density is zero.
+    pub fn sample(&self, u: (f64, f64)) -> Option<(Vec3, f64)> {
+        let ((x, y), pdf) = self.distribution.sample(u);
+        let sin_theta: f64 = (PI * y).sin();
+        match pdf > 0.0 && sin_theta > 0.0 {
+            // The image maps onto the sphere with 2 pi^2 sin(theta) steradians per unit area.
+            true => Some((
+                self.uv_to_direction(x, y),
+                pdf / (2.0 * PI * PI * sin_theta),
+            )),
+            false => None,
+        }
+    }
+
+    // Density per unit solid angle with which `sample` picks `direction`.
+    pub fn pdf(&self, direction: Vec3) -> f64 {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta: f64 = (PI * v).sin();
+        match sin_theta > 0.0 {
+            true => self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta),
+            false => 0.0,
+        }
+    }
+
+    // Image coordinates in [0, 1) x [0, 1] of a direction, (0, 0) being the top left corner.
+    pub fn direction_to_uv(&self, direction: Vec3) -> (f64, f64) {
+        let d: Vec3 = unit_vector(direction);
+        let azimuth: f64 = d.x().atan2(-d.z());
+        let u: f64 = ((azimuth + self.rotation) / (2.0 * PI) + 0.5).rem_euclid(1.0);
+        let v: f64 = d.y().clamp(-1.0, 1.0).acos() / PI;
+        (u, v)
+    }
+
+    // Unit direction through image coordinates (u, v); the inverse of `direction_to_uv`.
+    pub fn uv_to_direction(&self, u: f64, v: f64) -> Vec3 {
+        let azimuth: f64 = 2.0 * PI * (u - 0.5) - self.rotation;
+        let (sin_theta, cos_theta) = (PI * v).sin_cos();
+        Vec3::new(
+            sin_theta * azimuth.sin(),
+            cos_theta,
+            -sin_theta * azimuth.cos(),
+        )
+    }
+}
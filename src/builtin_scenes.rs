@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::{
+    camera::CameraConfig,
+    color::Color,
+    filter::Filter,
+    hittables::{hittable::HittableType, hittable_list::HittableList, sphere::Sphere},
+    materials::{dielectric::Dielectric, lambertian::Lambertian, material::Material, metal::Metal},
+    ray::Point3,
+    rng::Rng,
+    sampler::SamplerKind,
+    scene::Scene,
+    tile::TileConfig,
+    vec3::Vec3,
+};
+
+// Names accepted by `builtin_scene`, for help and error messages.
+pub const BUILTIN_SCENES: [&str; 1] = ["random-spheres"];
+
+// `seed` drives both the layout of randomly generated scenes and the render itself.
+pub fn builtin_scene(name: &str, seed: u64) -> Option<Scene> {
+    match name {
+        "random-spheres" => Some(random_spheres(seed)),
+        _ => None,
+    }
+}
+
+// The final scene of the book: a field of small random spheres around three large ones.
+fn random_spheres(seed: u64) -> Scene {
+    let mut rng: Rng = Rng::new(seed);
+    let mut world: HittableList = HittableList::default();
+
+    let ground_material: Material =
+        Material::Lambertian(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
+    let sphere_ground: HittableType = HittableType::Sphere(Arc::new(Sphere::new(
+        &Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground_material,
+    )));
+    world.add(sphere_ground);
+
+    let fixed_point: &Point3 = &Point3::new(4.0, 0.2, 0.0);
+    for a in -11..11 {
+        for b in -11..11 {
+            let rand_double: f64 = rng.random_double();
+            let center: Point3 = Point3::new(
+                a as f64 + 0.9 * rng.random_double(),
+                0.2,
+                b as f64 + 0.9 * rng.random_double(),
+            );
+            if (center - *fixed_point).length() <= 0.9 {
+                continue;
+            }
+
+            let sphere: HittableType = match rand_double {
+                rd if rd < 0.8 => {
+                    // diffuse
+                    let albedo: Color = Color::random(&mut rng) * Color::random(&mut rng);
+                    let material: Material = Material::Lambertian(Lambertian::new(albedo));
+                    HittableType::Sphere(Arc::new(Sphere::new(&center, 0.2, material)))
+                }
+                rd if rd < 0.95 => {
+                    // metal
+                    let albedo: Color = Color::random_range(&mut rng, 0.5, 1.0);
+                    let fuzz: f64 = rng.random_double_range(0.0, 0.5);
+                    let material: Material = Material::Metal(Metal::new(albedo, fuzz));
+                    HittableType::Sphere(Arc::new(Sphere::new(&center, 0.2, material)))
+                }
+                _ => {
+                    // glass
+                    let material: Material = Material::Dielectric(Dielectric::new(1.5));
+                    HittableType::Sphere(Arc::new(Sphere::new(&center, 0.2, material)))
+                }
+            };
+
+            world.add(sphere);
+        }
+    }
+
+    let material1: Material = Material::Dielectric(Dielectric::new(1.5));
+    world.add(HittableType::Sphere(Arc::new(Sphere::new(
+        &Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        material1,
+    ))));
+
+    let material2: Material = Material::Lambertian(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
+    world.add(HittableType::Sphere(Arc::new(Sphere::new(
+        &Color::new(-4.0, 1.0, 0.0),
+        1.0,
+        material2,
+    ))));
+
+    let material3: Material = Material::Metal(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
+    world.add(HittableType::Sphere(Arc::new(Sphere::new(
+        &Color::new(4.0, 1.0, 0.0),
+        1.0,
+        material3,
+    ))));
+
+    let camera_config = CameraConfig {
+        aspect_ratio: 16.0 / 9.0,
+        image_width: 1200.0,
+        samples_per_pixel: 500.0,
+        max_depth: 50,
+        vfov: 20.0,
+        look_from: Point3::new(13.0, 2.0, 3.0),
+        look_at: Point3::new(0.0, 0.0, 0.0),
+        v_up: Vec3::new(0.0, 0.2, 0.0),
+        defocus_angle: 0.6,
+        focus_dist: 10.0,
+        seed,
+        tiles: TileConfig::default(),
+        adaptive: None,
+        sampler: SamplerKind::default(),
+        filter: Filter::default(),
+    };
+
+    Scene::new(world, camera_config)
+}
@@ -1,19 +1,28 @@
 use std::{
-    io::{self, Write},
-    sync::{Arc, Mutex},
-    time::SystemTime,
+    fmt,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Instant,
 };
 
 use rayon::prelude::*; // Ensure rayon's parallel iterator traits are imported
 
 use crate::{
-    color::{build_color, write_color, Color},
+    accumulator::{Accumulator, PixelSamples, TileSamples},
+    background::Background,
+    color::Color,
+    filter::Filter,
+    framebuffer::Framebuffer,
     hittables::hittable::HittableType,
+    integrator::Integrator,
+    progress::{CancellationToken, NoProgress, Progress, ProgressObserver},
     ray::{Point3, Ray},
-    util::{degrees_to_radians, random_double},
+    sampler::{Sampler, SamplerKind},
+    tile::{Tile, TileConfig},
+    util::degrees_to_radians,
     vec3::{cross, random_in_unit_disk, unit_vector, Vec3},
 };
 
+#[derive(Clone, Copy, Debug)]
 pub struct CameraConfig {
     pub aspect_ratio: f64,
     pub image_width: f64,
@@ -25,10 +34,37 @@ pub struct CameraConfig {
     pub v_up: Vec3,
     pub defocus_angle: f64,
     pub focus_dist: f64,
+    pub seed: u64,
+    pub tiles: TileConfig,
+    pub adaptive: Option<AdaptiveSampling>,
+    pub sampler: SamplerKind,
+    pub filter: Filter,
+}
+
+// Stops sampling a pixel once its estimated display error (see `PixelSamples::display_error`)
+// is at most `threshold`, after at least `min_samples` and at most samples_per_pixel samples.
+// Convergence depends only on a pixel's own samples, so adaptive renders stay reproducible.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveSampling {
+    pub threshold: f64,
+    pub min_samples: u32,
+}
+
+impl AdaptiveSampling {
+    pub const DEFAULT_MIN_SAMPLES: u32 = 16;
+}
+
+// What a render produced: the image and the samples behind it, how far it got and whether it was
+// cancelled.
+#[derive(Clone, Debug)]
+pub struct RenderOutput {
+    pub image: Framebuffer,
+    pub accumulator: Accumulator,
+    pub progress: Progress,
+    pub cancelled: bool,
 }
 
 pub struct Camera {
-    pixel_samples_scale: f64,
     image_width: f64,
     samples_per_pixel: f64,
     max_depth: i16,
@@ -40,79 +76,200 @@ pub struct Camera {
     defocus_angle: f64,
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
+    seed: u64,
+    tiles: TileConfig,
+    adaptive: Option<AdaptiveSampling>,
+    sampler: SamplerKind,
+    filter: Filter,
+    background: Background,
 }
 
 impl Camera {
-    pub fn render(self, world: HittableType) {
-        let render_sw = SystemTime::now();
-        let mut stdout = io::stdout();
-        let file = std::fs::File::create("image.ppm").expect("Image file to be created");
-        let mut buff = std::io::BufWriter::new(file);
-        writeln!(buff, "P3\n").expect("to write ppm metadata");
-        writeln!(buff, "{} {}\n", self.image_width, self.image_height)
-            .expect("to write ppm metadata");
-        writeln!(buff, "255\n").expect("to write ppm metadata");
-
-        let default_value: [u8; 11] = [b' '; 11];
-        let colors = Mutex::new(vec![
-            default_value;
-            (self.image_height * self.image_width) as usize
-        ]);
-        let colors_len: &usize = &colors.lock().unwrap().len();
-        let completed_ct: Arc<Mutex<i64>> = Arc::new(Mutex::new(0));
-
-        // Parallelize the outer loop
-        (0..(self.image_height as i16))
-            .into_par_iter() // Convert into a parallel iterator
-            .for_each(|j| {
-                for i in 0..(self.image_width as i16) {
-                    let mut pixel_color: Color = Color::default();
-                    for _ in 0..self.samples_per_pixel as i64 {
-                        let ray: Ray = self.get_ray(i as f64, j as f64);
-                        pixel_color += Ray::ray_color(&ray, self.max_depth, &world);
-                    }
-                    let color: [u8; 11] = build_color(self.pixel_samples_scale * pixel_color);
-                    let mut colors_guard = colors.lock().unwrap();
-                    let idx = (j as usize * self.image_width as usize) + i as usize;
-
-                    colors_guard[idx] = color;
-                    let mut count_guard = completed_ct.lock().unwrap();
-                    *count_guard += 1;
-                    print!(
-                        "\r|Render| Progress: {:.3}%",
-                        (*count_guard as f32 / (*colors_len - 1) as f32) * 100.0
-                    );
-                }
-            });
+    // Renders the world into a linear framebuffer of image_width x image_height pixels.
+    pub fn render(&self, world: &HittableType) -> Framebuffer {
+        self.render_with(world, &NoProgress, &CancellationToken::new())
+            .image
+    }
 
-        stdout.flush().unwrap();
-        println!();
-        println!(
-            "Render time (minutes): {}",
-            render_sw.elapsed().unwrap().as_secs() as f32 / 60.0
+    // Like `render`, reporting to `observer` as tiles finish and stopping early once `cancel` is
+    // cancelled. A cancelled render returns each pixel's mean over the samples taken so far, so
+    // pixels that got none are black.
+    pub fn render_with(
+        &self,
+        world: &HittableType,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+    ) -> RenderOutput {
+        let samples_per_pixel: u32 = self.samples_per_pixel as u32;
+        self.render_progressive(world, samples_per_pixel, observer, cancel, &mut |_, _| {})
+    }
+
+    // Renders in passes of `samples_per_pass` samples over the whole image, calling `on_pass`
+    // with the number of finished passes and the samples gathered so far after each one. Every
+    // sample draws from its own random stream, so the final image is the same for any pass size
+    // (with filters wider than a pixel, up to rounding in the last bits). A cancelled render
+    // returns the mean of whatever samples each pixel has.
+    pub fn render_progressive(
+        &self,
+        world: &HittableType,
+        samples_per_pass: u32,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+        on_pass: &mut dyn FnMut(u32, &Accumulator),
+    ) -> RenderOutput {
+        let (width, height) = self.image_size();
+        let accumulator: Accumulator = Accumulator::new(width, height);
+        self.render_passes(
+            world,
+            accumulator,
+            samples_per_pass,
+            observer,
+            cancel,
+            on_pass,
+        )
+    }
+
+    // Continues a progressive render from previously accumulated samples, such as a loaded
+    // checkpoint, until every pixel has samples_per_pixel samples or has converged. Pixels that
+    // are already done are left alone, so raising samples_per_pixel refines a finished render.
+    // Fails if the samples are for a different image size.
+    pub fn resume(
+        &self,
+        world: &HittableType,
+        accumulator: Accumulator,
+        samples_per_pass: u32,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+        on_pass: &mut dyn FnMut(u32, &Accumulator),
+    ) -> Result<RenderOutput, RenderError> {
+        let expected: (usize, usize) = self.image_size();
+        let found: (usize, usize) = (accumulator.width(), accumulator.height());
+        if found != expected {
+            return Err(RenderError::SizeMismatch { expected, found });
+        }
+        Ok(self.render_passes(
+            world,
+            accumulator,
+            samples_per_pass,
+            observer,
+            cancel,
+            on_pass,
+        ))
+    }
+
+    // `resume` for an accumulator of the right size.
+    fn render_passes(
+        &self,
+        world: &HittableType,
+        mut accumulator: Accumulator,
+        samples_per_pass: u32,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+        on_pass: &mut dyn FnMut(u32, &Accumulator),
+    ) -> RenderOutput {
+        let start: Instant = Instant::now();
+        let (width, height) = self.image_size();
+        let samples_per_pixel: u32 = self.samples_per_pixel as u32;
+        let tiles: Vec<Tile> = self.tiles.tiles(width, height);
+        let margin: usize = self.filter.margin();
+        let pixels: &[PixelSamples] = accumulator.pixels();
+        let samples_ct: AtomicU64 = AtomicU64::new(
+            pixels
+                .iter()
+                .map(|p| p.count.min(samples_per_pixel) as u64)
+                .sum(),
+        );
+        let skipped_ct: AtomicU64 = AtomicU64::new(
+            pixels
+                .iter()
+                .filter(|p| self.is_done(p))
+                .map(|p| samples_per_pixel.saturating_sub(p.count) as u64)
+                .sum(),
         );
+        let completed_ct: AtomicUsize =
+            AtomicUsize::new(pixels.iter().filter(|p| self.is_done(p)).count());
+        let fewest: u32 = pixels
+            .iter()
+            .filter(|p| !self.is_done(p))
+            .map(|p| p.count)
+            .min()
+            .unwrap_or(samples_per_pixel);
+        let progress = |completed: usize, samples: u64, skipped: u64| Progress {
+            completed_pixels: completed,
+            total_pixels: width * height,
+            samples,
+            skipped_samples: skipped,
+            total_samples: (width * height) as u64 * samples_per_pixel as u64,
+            elapsed: start.elapsed(),
+        };
 
-        stdout.flush().unwrap();
-        println!();
+        let integrator: Integrator = Integrator::new(world, &self.background);
+        let samples_per_pass: u32 = samples_per_pass.clamp(1, samples_per_pixel.max(1));
+        let passes: u32 = samples_per_pixel.div_ceil(samples_per_pass);
+        // Skip the passes every pixel has already been through.
+        for pass in (fewest / samples_per_pass + 1)..=passes {
+            let target: u32 = (pass * samples_per_pass).min(samples_per_pixel);
+            let next_tile: AtomicUsize = AtomicUsize::new(0);
+            let current: &Accumulator = &accumulator;
 
-        // Write all the colors to the file
-        let write_sw = SystemTime::now();
-        let color_len = colors.lock().unwrap().len();
-        for (i, color) in colors.lock().unwrap().iter().enumerate() {
-            print!(
-                "\r|Write| Progress: {:.3}%",
-                (i as f32 / (color_len - 1) as f32) * 100.0
-            );
-            write_color(&mut buff, u8_to_string(color));
+            // Every worker keeps claiming the next tile in order until none are left, so tiles
+            // start in the configured order, and renders it into its own buffer.
+            let mut rendered: Vec<(usize, TileSamples)> = (0..rayon::current_num_threads())
+                .into_par_iter()
+                .flat_map_iter(|_| {
+                    let mut done: Vec<(usize, TileSamples)> = Vec::new();
+                    loop {
+                        let index: usize = next_tile.fetch_add(1, Ordering::Relaxed);
+                        let Some(tile) = tiles.get(index) else {
+                            break;
+                        };
+                        if cancel.is_cancelled() {
+                            break;
+                        }
+                        let mut samples: TileSamples = current.read_tile(tile, margin);
+                        let work: TileWork =
+                            self.render_tile(&mut samples, target, &integrator, cancel);
+                        done.push((index, samples));
+                        let samples: u64 =
+                            samples_ct.fetch_add(work.traced, Ordering::Relaxed) + work.traced;
+                        let skipped: u64 =
+                            skipped_ct.fetch_add(work.skipped, Ordering::Relaxed) + work.skipped;
+                        let completed: usize = completed_ct
+                            .fetch_add(work.finished, Ordering::Relaxed)
+                            + work.finished;
+                        observer.on_progress(&progress(completed, samples, skipped));
+                    }
+                    done
+                })
+                .collect();
+
+            // Margins overlap, so add them in tile order to get the same sums on every run.
+            rendered.sort_unstable_by_key(|(index, _)| *index);
+            for (_, samples) in &rendered {
+                accumulator.write_tile(samples);
+            }
+            for (_, samples) in &rendered {
+                accumulator.add_margin(samples);
+            }
+            if cancel.is_cancelled() {
+                break;
+            }
+            on_pass(pass, &accumulator);
         }
 
-        stdout.flush().unwrap();
-        println!();
-        println!(
-            "Write time (minutes): {}",
-            write_sw.elapsed().unwrap().as_secs() as f32 / 60.0
+        let progress: Progress = progress(
+            completed_ct.into_inner(),
+            samples_ct.into_inner(),
+            skipped_ct.into_inner(),
         );
-        println!("Done.");
+        let cancelled: bool = progress.completed_pixels < progress.total_pixels;
+        observer.on_finish(&progress, cancelled);
+        RenderOutput {
+            image: accumulator.resolve(),
+            accumulator,
+            progress,
+            cancelled,
+        }
     }
 
     pub fn new(config: CameraConfig) -> Self {
@@ -122,8 +279,6 @@ impl Camera {
             n => n.round(),
         };
 
-        let pixel_samples_scale: f64 = 1.0 / config.samples_per_pixel;
-
         let center: Point3 = config.look_from;
 
         // Determine the viewport dimensions.
@@ -161,7 +316,6 @@ impl Camera {
             max_depth: config.max_depth,
             samples_per_pixel: config.samples_per_pixel,
             defocus_angle: config.defocus_angle,
-            pixel_samples_scale,
             image_height,
             center,
             pixel00_loc,
@@ -169,45 +323,338 @@ impl Camera {
             pixel_delta_v,
             defocus_disk_u,
             defocus_disk_v,
+            seed: config.seed,
+            tiles: config.tiles,
+            adaptive: config.adaptive,
+            sampler: config.sampler,
+            filter: config.filter,
+            background: Background::default(),
+        }
+    }
+
+    // Sets what rays that miss everything see.
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn image_size(&self) -> (usize, usize) {
+        (self.image_width as usize, self.image_height as usize)
+    }
+
+    // Whether a pixel needs no more samples: it has samples_per_pixel or has converged.
+    fn is_done(&self, pixel: &PixelSamples) -> bool {
+        pixel.count >= self.samples_per_pixel as u32
+            || self.adaptive.is_some_and(|adaptive| {
+                pixel.count >= adaptive.min_samples && pixel.display_error() <= adaptive.threshold
+            })
+    }
+
+    // Brings every pixel of the tile that is not done up to `target` samples, stopping early at
+    // any cancellation.
+    fn render_tile(
+        &self,
+        samples: &mut TileSamples,
+        target: u32,
+        integrator: &Integrator,
+        cancel: &CancellationToken,
+    ) -> TileWork {
+        let samples_per_pixel: u32 = self.samples_per_pixel as u32;
+        let mut work: TileWork = TileWork::default();
+        let tile: Tile = *samples.tile();
+        let coords = (tile.y..tile.y + tile.height)
+            .flat_map(|j| (tile.x..tile.x + tile.width).map(move |i| (i, j)));
+        for (i, j) in coords {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let before: u32 = samples.get(i, j).count;
+            if before >= target || self.is_done(samples.get(i, j)) {
+                continue;
+            }
+            self.render_pixel(i, j, samples, target, integrator);
+            let pixel: &PixelSamples = samples.get(i, j);
+            work.traced += (pixel.count - before) as u64;
+            if self.is_done(pixel) {
+                work.finished += 1;
+                work.skipped += samples_per_pixel.saturating_sub(pixel.count) as u64;
+            }
+        }
+        work
+    }
+
+    fn render_pixel(
+        &self,
+        i: usize,
+        j: usize,
+        samples: &mut TileSamples,
+        target: u32,
+        integrator: &Integrator,
+    ) {
+        let index: u64 = (j * self.image_width as usize + i) as u64;
+        loop {
+            let pixel: &PixelSamples = samples.get(i, j);
+            if pixel.count >= target || self.is_done(pixel) {
+                break;
+            }
+            let mut sampler: Sampler =
+                Sampler::new(self.sampler, self.seed, index, pixel.count as u64);
+            // Image-plane position of the sample, in pixels from the centre of pixel (0, 0).
+            let offset: Vec3 = Camera::sample_square(&mut sampler);
+            let (x, y) = (i as f64 + offset.x(), j as f64 + offset.y());
+            let ray: Ray = self.get_ray(x, y, &mut sampler);
+            let radiance: Color = integrator.radiance(&ray, self.max_depth, &mut sampler);
+            samples.get_mut(i, j).add(radiance);
+            self.splat(samples, x, y, radiance);
+        }
+    }
+
+    // Adds a sample at image-plane position (x, y) to every pixel within the filter radius, each
+    // weighted by the filter at its distance from the pixel's centre.
+    fn splat(&self, samples: &mut TileSamples, x: f64, y: f64, radiance: Color) {
+        let (width, height) = self.image_size();
+        let radius: f64 = self.filter.radius;
+        // Pixels whose centres lie in (p - radius, p + radius]; half-open, so that with a box of
+        // radius 0.5 every sample lands in exactly one pixel.
+        let range = |p: f64, size: usize| {
+            let first: usize = ((p - radius).floor() + 1.0).max(0.0) as usize;
+            let last: usize = ((p + radius).floor().max(0.0) as usize).min(size - 1);
+            first..=last
+        };
+        for py in range(y, height) {
+            for px in range(x, width) {
+                let weight: f64 = self.filter.evaluate(px as f64 - x, py as f64 - y);
+                if weight != 0.0 {
+                    samples.splat(px, py, radiance, weight);
+                }
+            }
         }
     }
 
-    fn get_ray(&self, i: f64, j: f64) -> Ray {
-        // Construct a camera ray originating from the origin and directed at
-        // randomly sampled point around the pixel location i, j.
-        let offset = Camera::sample_square();
-        let pixel_sample = self.pixel00_loc
-            + ((i + offset.x()) * self.pixel_delta_u)
-            + ((j + offset.y()) * self.pixel_delta_v);
+    // Camera ray from the camera centre, or a random point of the defocus disk, through
+    // image-plane position (x, y).
+    fn get_ray(&self, x: f64, y: f64, sampler: &mut Sampler) -> Ray {
+        let pixel_sample = self.pixel00_loc + (x * self.pixel_delta_u) + (y * self.pixel_delta_v);
         let ray_origin: Vec3 = match self.defocus_angle <= 0.0 {
             true => self.center,
-            false => self.defocus_disk_sample(),
+            false => self.defocus_disk_sample(sampler),
         };
         let ray_direction: Vec3 = pixel_sample - ray_origin;
         Ray::new(ray_origin, ray_direction)
     }
 
-    fn sample_square() -> Vec3 {
-        Vec3::new(random_double() - 0.5, random_double() - 0.5, 0.0)
+    fn sample_square(sampler: &mut Sampler) -> Vec3 {
+        let (u, v) = sampler.get_2d();
+        Vec3::new(u - 0.5, v - 0.5, 0.0)
     }
 
-    fn defocus_disk_sample(&self) -> Point3 {
+    fn defocus_disk_sample(&self, sampler: &mut Sampler) -> Point3 {
         // Returns a random point in the camera defocus disk.
-        let p: Vec3 = random_in_unit_disk();
+        let p: Vec3 = random_in_unit_disk(sampler);
         self.center + (p.e[0] * self.defocus_disk_u) + (p.e[1] * self.defocus_disk_v)
     }
 }
 
-fn u8_to_string(bytes: &[u8; 11]) -> String {
-    std::str::from_utf8(bytes)
-        .expect("Invalid UTF-8")
-        .to_string()
+// Reasons a render can't go ahead.
+#[derive(Debug)]
+pub enum RenderError {
+    // Accumulated samples handed to `resume` are for a different image size, as width x height.
+    SizeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeMismatch { expected, found } => write!(
+                f,
+                "accumulated samples are for a {}x{} image but the render is {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+// Work done on one tile: samples traced, pixels newly done, and samples those pixels did not
+// need because they converged early.
+#[derive(Clone, Copy, Debug, Default)]
+struct TileWork {
+    traced: u64,
+    finished: usize,
+    skipped: u64,
 }
 
-pub fn string_to_u8(string: &str) -> [u8; 11] {
-    let mut array = [b' '; 11]; // Initialize with spaces
-    let bytes = string.as_bytes();
-    let len = bytes.len().min(11); // Truncate if necessary
-    array[..len].copy_from_slice(&bytes[..len]);
-    array
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{checkpoint::Checkpoint, scene::Scene, tile::TileOrder};
+
+    // A small lit scene with diffuse, glossy and glass surfaces, so every kind of scattering and
+    // the light sampling draw random numbers.
+    const SCENE: &str = r#"
+        [render]
+        image_width = 24
+        aspect_ratio = 1.5
+        samples_per_pixel = 6
+        max_depth = 8
+        seed = 7
+
+        [camera]
+        look_from = [0.0, 1.0, 4.0]
+        look_at = [0.0, 0.5, 0.0]
+        defocus_angle = 2.0
+        focus_dist = 4.0
+
+        [materials.ground]
+        type = "lambertian"
+        albedo = [0.5, 0.5, 0.5]
+
+        [materials.gold]
+        type = "metal"
+        albedo = [0.9, 0.8, 0.5]
+        fuzz = 0.3
+
+        [materials.glass]
+        type = "dielectric"
+        refraction_index = 1.5
+
+        [materials.lamp]
+        type = "diffuse_light"
+        emit = [8.0, 8.0, 8.0]
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, -100.0, 0.0]
+        radius = 100.0
+        material = "ground"
+
+        [[objects]]
+        type = "sphere"
+        center = [-0.6, 0.5, 0.0]
+        radius = 0.5
+        material = "gold"
+
+        [[objects]]
+        type = "sphere"
+        center = [0.6, 0.5, 0.0]
+        radius = 0.5
+        material = "glass"
+
+        [[objects]]
+        type = "sphere"
+        center = [0.0, 2.5, 1.0]
+        radius = 0.4
+        material = "lamp"
+    "#;
+
+    fn scene() -> Scene {
+        Scene::parse(SCENE, Path::new("test.toml")).unwrap()
+    }
+
+    fn render_on(threads: usize, tiles: TileConfig) -> Framebuffer {
+        let mut scene: Scene = scene();
+        scene.camera_config.tiles = tiles;
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+            .install(|| scene.render())
+    }
+
+    #[test]
+    fn same_image_for_any_thread_count_and_tile_order() {
+        let reference: Framebuffer = render_on(
+            1,
+            TileConfig {
+                size: 32,
+                order: TileOrder::Scanline,
+            },
+        );
+        assert!(reference.pixels().iter().any(|p| p[0] > 0.0));
+        for (threads, size, order) in [
+            (4, 8, TileOrder::Spiral),
+            (3, 5, TileOrder::Hilbert),
+            (2, 1, TileOrder::Scanline),
+        ] {
+            let image: Framebuffer = render_on(threads, TileConfig { size, order });
+            assert!(
+                image.pixels() == reference.pixels(),
+                "{} threads with {}-pixel {:?} tiles",
+                threads,
+                size,
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn resumed_checkpoint_matches_full_render() {
+        // Resuming with more samples than the checkpoint was rendered with, across a round of
+        // the stratified sampler's strata.
+        for sampler in [
+            SamplerKind::Independent,
+            SamplerKind::Stratified,
+            SamplerKind::Halton,
+            SamplerKind::Sobol,
+        ] {
+            let with_samples = |samples_per_pixel: f64| {
+                let mut scene: Scene = scene();
+                scene.camera_config.sampler = sampler;
+                scene.camera_config.samples_per_pixel = samples_per_pixel;
+                scene
+            };
+            let full: Framebuffer = with_samples(20.0).render();
+
+            let output: RenderOutput = with_samples(5.0).render_progressive(
+                2,
+                &NoProgress,
+                &CancellationToken::new(),
+                &mut |_, _| {},
+            );
+            let mut saved: Vec<u8> = Vec::new();
+            Checkpoint {
+                seed: 7,
+                scene_id: 0,
+                accumulator: output.accumulator,
+            }
+            .write(&mut saved)
+            .unwrap();
+            let checkpoint: Checkpoint = Checkpoint::read(&mut saved.as_slice()).unwrap();
+
+            let resumed: RenderOutput = with_samples(20.0)
+                .resume(
+                    checkpoint.accumulator,
+                    3,
+                    &NoProgress,
+                    &CancellationToken::new(),
+                    &mut |_, _| {},
+                )
+                .unwrap();
+            assert!(resumed.image.pixels() == full.pixels(), "{:?}", sampler);
+        }
+    }
+
+    #[test]
+    fn resume_rejects_samples_for_another_size() {
+        let result = scene().resume(
+            Accumulator::new(10, 10),
+            1,
+            &NoProgress,
+            &CancellationToken::new(),
+            &mut |_, _| {},
+        );
+        assert!(matches!(
+            result,
+            Err(RenderError::SizeMismatch {
+                expected: (24, 16),
+                found: (10, 10)
+            })
+        ));
+    }
 }
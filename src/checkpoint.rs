@@ -0,0 +1,134 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    accumulator::{Accumulator, PixelSamples},
+    color::Color,
+};
+
+const MAGIC: &[u8; 8] = b"RTIOWCKP";
+const VERSION: u32 = 3;
+
+// Saved state of an unfinished (or finished) render: the filtered radiance sums and sample counts
+// of every pixel. Each camera sample draws from a stream keyed on (seed, pixel, sample index), so
+// the seed and the counts are all the random number state needed to carry on where it stopped.
+//
+// The file is little endian: magic, version, width, height, seed, scene id, then per pixel the
+// three f64 weighted radiance sums, the f64 weight sum, a u32 sample count and the f64 luminance
+// mean and squared deviation sum, row-major with the top row first.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub seed: u64,
+    // Caller-chosen fingerprint of whatever else determines the image (scene, camera, depth),
+    // checked before resuming so samples from different renders are never mixed.
+    pub scene_id: u64,
+    pub accumulator: Accumulator,
+}
+
+impl Checkpoint {
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        let accumulator: &Accumulator = &self.accumulator;
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&(accumulator.width() as u32).to_le_bytes())?;
+        out.write_all(&(accumulator.height() as u32).to_le_bytes())?;
+        out.write_all(&self.seed.to_le_bytes())?;
+        out.write_all(&self.scene_id.to_le_bytes())?;
+        for pixel in accumulator.pixels() {
+            for c in pixel.sum.e {
+                out.write_all(&c.to_le_bytes())?;
+            }
+            out.write_all(&pixel.weight.to_le_bytes())?;
+            out.write_all(&pixel.count.to_le_bytes())?;
+            out.write_all(&pixel.luminance_mean.to_le_bytes())?;
+            out.write_all(&pixel.luminance_m2.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read(input: &mut impl Read) -> io::Result<Self> {
+        let mut magic: [u8; 8] = [0; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a render checkpoint"));
+        }
+        let version: u32 = read_u32(input)?;
+        if version != VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported checkpoint version {}",
+                version
+            )));
+        }
+        let width: usize = read_u32(input)? as usize;
+        let height: usize = read_u32(input)? as usize;
+        let seed: u64 = read_u64(input)?;
+        let scene_id: u64 = read_u64(input)?;
+
+        // Let a truncated file fail on reading rather than on a huge up-front allocation.
+        let mut pixels: Vec<PixelSamples> = Vec::with_capacity((width * height).min(1 << 20));
+        for _ in 0..width * height {
+            let mut sum: Color = Color::default();
+            for c in sum.e.iter_mut() {
+                *c = f64::from_bits(read_u64(input)?);
+            }
+            let weight: f64 = f64::from_bits(read_u64(input)?);
+            let count: u32 = read_u32(input)?;
+            let luminance_mean: f64 = f64::from_bits(read_u64(input)?);
+            let luminance_m2: f64 = f64::from_bits(read_u64(input)?);
+            pixels.push(PixelSamples {
+                sum,
+                weight,
+                count,
+                luminance_mean,
+                luminance_m2,
+            });
+        }
+        Ok(Self {
+            seed,
+            scene_id,
+            accumulator: Accumulator::from_pixels(width, height, pixels),
+        })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    // Saves through a temporary sibling file, so a crash mid-write leaves the previous checkpoint.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temp: OsString = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp: PathBuf = PathBuf::from(temp);
+        let mut buff = BufWriter::new(File::create(&temp)?);
+        self.write(&mut buff)?;
+        buff.into_inner()?.sync_all()?;
+        fs::rename(&temp, path)
+    }
+}
+
+// 64-bit FNV-1a hash, stable across platforms and releases, for building scene ids.
+pub fn fingerprint(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+    let mut bytes: [u8; 4] = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut bytes: [u8; 8] = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,411 @@
+use std::path::{Path, PathBuf};
+
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Parser, ValueEnum,
+};
+
+use ray_tracing_in_one_weekend::{
+    builtin_scene, checkpoint::fingerprint, AdaptiveSampling, BitDepth, CameraConfig, Filter,
+    FilterKind, ImageFormat, ImageWriter, SamplerKind, Scene, TileOrder, ToneMapper, ToneMapping,
+    BUILTIN_SCENES,
+};
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "Renders a scene with the Ray Tracing in One Weekend path tracer"
+)]
+pub struct Args {
+    /// Scene file (.toml) or the name of a built-in scene (random-spheres)
+    #[arg(default_value = "random-spheres")]
+    pub scene: String,
+
+    /// Output image path
+    #[arg(short, long, default_value = "image.ppm")]
+    pub output: PathBuf,
+
+    /// Output image format [default: taken from the output file extension]
+    #[arg(short, long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Bits per color channel: 8 or 16 for PPM and PNG, 16 (half) or 32 (float) for OpenEXR
+    /// [default: 8 for PPM and PNG, 16 for OpenEXR, 32 for PFM and Radiance HDR]
+    #[arg(
+        long,
+        value_parser = PossibleValuesParser::new(["8", "16", "32"]).map(|s| s.parse::<u8>().unwrap())
+    )]
+    pub bit_depth: Option<u8>,
+
+    /// Tone-mapping operator for PPM and PNG output; PFM, HDR and OpenEXR stay linear
+    #[arg(long, value_enum, default_value_t = ToneMap::Clamp)]
+    pub tonemap: ToneMap,
+
+    /// Exposure adjustment in stops (EV) applied before tone mapping
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    pub exposure: f64,
+
+    /// Linear luminance mapped to white by the reinhard operator
+    #[arg(long, default_value_t = 4.0, value_parser = parse_positive)]
+    pub white_point: f64,
+
+    /// Image width in pixels [default: from the scene]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: Option<u32>,
+
+    /// Image height in pixels; together with --width this also sets the aspect ratio
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: Option<u32>,
+
+    /// Samples per pixel [default: from the scene]
+    #[arg(short, long = "spp", value_parser = clap::value_parser!(u32).range(1..))]
+    pub samples_per_pixel: Option<u32>,
+
+    /// Maximum number of ray bounces [default: from the scene]
+    #[arg(short = 'd', long, value_parser = clap::value_parser!(i16).range(1..))]
+    pub max_depth: Option<i16>,
+
+    /// Number of render threads [default: one per logical core]
+    #[arg(short = 'j', long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub threads: Option<u32>,
+
+    /// Render progressively in passes of this many samples per pixel, updating the output as it goes
+    #[arg(long, value_name = "SAMPLES_PER_PASS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub progressive: Option<u32>,
+
+    /// With --progressive, write a snapshot every N passes
+    #[arg(long, value_name = "N", requires = "progressive", value_parser = clap::value_parser!(u32).range(1..))]
+    pub snapshot_passes: Option<u32>,
+
+    /// With --progressive, write a snapshot once this many seconds have passed since the last
+    /// [default: 10 unless --snapshot-passes is given]
+    #[arg(long, value_name = "SECS", requires = "progressive", value_parser = parse_positive)]
+    pub snapshot_interval: Option<f64>,
+
+    /// Save the accumulated samples to this checkpoint file periodically and when the render
+    /// stops, so it can be resumed with --resume
+    #[arg(long, value_name = "PATH")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Seconds between checkpoints
+    #[arg(long, value_name = "SECS", default_value_t = 60.0, requires = "checkpoint", value_parser = parse_positive)]
+    pub checkpoint_interval: f64,
+
+    /// Continue from the --checkpoint file instead of starting over; with a higher --spp this
+    /// also refines a finished render
+    #[arg(long, requires = "checkpoint")]
+    pub resume: bool,
+
+    /// Adaptive sampling: stop sampling a pixel once its estimated error after display encoding
+    /// drops below this (e.g. 0.01); --spp becomes the maximum [default: from the scene]
+    #[arg(long, value_name = "THRESHOLD", value_parser = parse_positive)]
+    pub adaptive: Option<f64>,
+
+    /// Samples every pixel gets before adaptive sampling may stop it; needs adaptive sampling
+    /// from --adaptive or the scene [default: from the scene, or 16]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    pub min_spp: Option<u32>,
+
+    /// Also write an image of how many samples each pixel received
+    #[arg(long, value_name = "PATH")]
+    pub sample_heatmap: Option<PathBuf>,
+
+    /// Stop rendering after this many seconds and write the partially rendered image
+    #[arg(long, value_parser = parse_positive)]
+    pub time_limit: Option<f64>,
+
+    /// Sample pattern for pixel, lens and scattering samples [default: from the scene, or sobol]
+    #[arg(long, value_enum)]
+    pub sampler: Option<SamplerChoice>,
+
+    /// Reconstruction filter weighting samples into nearby pixels [default: from the scene, or box]
+    #[arg(long, value_enum)]
+    pub filter: Option<FilterChoice>,
+
+    /// Reconstruction filter radius in pixels [default: depends on the filter]
+    #[arg(long, value_name = "PX", value_parser = parse_positive)]
+    pub filter_radius: Option<f64>,
+
+    /// Edge length in pixels of the square tiles the image is rendered in
+    #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u32).range(1..))]
+    pub tile_size: u32,
+
+    /// Order in which tiles are rendered
+    #[arg(long, value_enum, default_value_t = TileOrdering::Spiral)]
+    pub tile_order: TileOrdering,
+
+    /// Seed for the sampling pattern and the layout of built-in scenes [default: from the scene, or 0]
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Binary portable pixmap (P6)
+    Ppm,
+    /// ASCII portable pixmap (P3)
+    PpmAscii,
+    /// Portable Network Graphics
+    Png,
+    /// Portable float map, linear 32-bit float
+    Pfm,
+    /// Radiance RGBE, linear shared-exponent
+    Hdr,
+    /// OpenEXR, linear half or float
+    Exr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ToneMap {
+    /// Clip channels to [0, 1]
+    Clamp,
+    /// Extended Reinhard on luminance, with --white-point
+    Reinhard,
+    /// ACES filmic curve fit
+    Aces,
+    /// Hable's Uncharted 2 filmic curve
+    Hable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SamplerChoice {
+    /// Independent uniform random numbers
+    Independent,
+    /// Jittered, shuffled strata
+    Stratified,
+    /// Randomly rotated Halton sequence
+    Halton,
+    /// Owen-scrambled Sobol sequence
+    Sobol,
+}
+
+impl From<SamplerChoice> for SamplerKind {
+    fn from(choice: SamplerChoice) -> Self {
+        match choice {
+            SamplerChoice::Independent => SamplerKind::Independent,
+            SamplerChoice::Stratified => SamplerKind::Stratified,
+            SamplerChoice::Halton => SamplerKind::Halton,
+            SamplerChoice::Sobol => SamplerKind::Sobol,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FilterChoice {
+    /// Plain average of the samples within the radius
+    Box,
+    /// Linear falloff
+    Tent,
+    /// Gaussian falloff
+    Gaussian,
+    /// Mitchell-Netravali cubic, slightly sharpening
+    Mitchell,
+    /// Windowed sinc, the sharpest, may ring at hard edges
+    Lanczos,
+}
+
+impl From<FilterChoice> for FilterKind {
+    fn from(choice: FilterChoice) -> Self {
+        match choice {
+            FilterChoice::Box => FilterKind::Box,
+            FilterChoice::Tent => FilterKind::Tent,
+            FilterChoice::Gaussian => FilterKind::Gaussian,
+            FilterChoice::Mitchell => FilterKind::Mitchell,
+            FilterChoice::Lanczos => FilterKind::Lanczos,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TileOrdering {
+    /// Left to right, top to bottom
+    Scanline,
+    /// Outwards from the centre of the image
+    Spiral,
+    /// Along a Hilbert curve
+    Hilbert,
+}
+
+impl From<TileOrdering> for TileOrder {
+    fn from(order: TileOrdering) -> Self {
+        match order {
+            TileOrdering::Scanline => TileOrder::Scanline,
+            TileOrdering::Spiral => TileOrder::Spiral,
+            TileOrdering::Hilbert => TileOrder::Hilbert,
+        }
+    }
+}
+
+impl From<OutputFormat> for ImageFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Ppm => ImageFormat::Ppm,
+            OutputFormat::PpmAscii => ImageFormat::PpmAscii,
+            OutputFormat::Png => ImageFormat::Png,
+            OutputFormat::Pfm => ImageFormat::Pfm,
+            OutputFormat::Hdr => ImageFormat::Hdr,
+            OutputFormat::Exr => ImageFormat::Exr,
+        }
+    }
+}
+
+impl Args {
+    // Writer for the --sample-heatmap image. The ramp's colors are linear, so 8- and 16-bit
+    // formats sRGB-encode them like a render, but without the --exposure and --tonemap settings.
+    pub fn heatmap_writer(&self) -> Result<Option<ImageWriter>, String> {
+        let Some(path) = &self.sample_heatmap else {
+            return Ok(None);
+        };
+        let format: ImageFormat = ImageFormat::from_path(path).ok_or_else(|| {
+            format!(
+                "cannot tell the image format of '{}' from its extension",
+                path.display()
+            )
+        })?;
+        ImageWriter::new(format, None).map(Some)
+    }
+
+    pub fn image_writer(&self) -> Result<ImageWriter, String> {
+        let format: ImageFormat = self
+            .format
+            .map(ImageFormat::from)
+            .or_else(|| ImageFormat::from_path(&self.output))
+            .ok_or_else(|| {
+                format!(
+                    "cannot tell the image format of '{}' from its extension; pass --format",
+                    self.output.display()
+                )
+            })?;
+        let bit_depth: Option<BitDepth> = self.bit_depth.map(|bits| match bits {
+            16 => BitDepth::Sixteen,
+            32 => BitDepth::ThirtyTwo,
+            _ => BitDepth::Eight,
+        });
+        let operator: ToneMapper = match self.tonemap {
+            ToneMap::Clamp => ToneMapper::Clamp,
+            ToneMap::Reinhard => ToneMapper::Reinhard {
+                white: self.white_point,
+            },
+            ToneMap::Aces => ToneMapper::Aces,
+            ToneMap::Hable => ToneMapper::Hable,
+        };
+        Ok(ImageWriter::new(format, bit_depth)?
+            .with_tone_mapping(ToneMapping::new(operator, self.exposure)))
+    }
+
+    pub fn load_scene(&self) -> Result<Scene, String> {
+        // Existing files and anything that looks like a scene file take precedence over names.
+        let path: &Path = Path::new(&self.scene);
+        if path.is_file() || path.extension().is_some_and(|e| e == "toml") {
+            return Scene::load(path).map_err(|e| e.to_string());
+        }
+        builtin_scene(&self.scene, self.seed.unwrap_or(0)).ok_or_else(|| {
+            format!(
+                "'{}' is neither a scene file nor a built-in scene ({})",
+                self.scene,
+                BUILTIN_SCENES.join(", ")
+            )
+        })
+    }
+
+    // Fingerprint of the scene description, the files it references and every camera setting
+    // that affects the image apart from the sample count and seed, to tell whether a checkpoint
+    // belongs to this render.
+    pub fn scene_id(&self, scene: &Scene) -> Result<u64, String> {
+        let read =
+            |path: &Path| std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e));
+        let path: &Path = Path::new(&self.scene);
+        let mut data: Vec<u8> = match path.is_file() {
+            true => read(path)?,
+            false => self.scene.as_bytes().to_vec(),
+        };
+        for asset in &scene.assets {
+            let contents: Vec<u8> = read(asset)?;
+            data.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+            data.extend_from_slice(&contents);
+        }
+
+        let config: &CameraConfig = &scene.camera_config;
+        let mut numbers: Vec<f64> = vec![
+            config.aspect_ratio,
+            config.image_width,
+            config.max_depth as f64,
+            config.vfov,
+            config.defocus_angle,
+            config.focus_dist,
+            config.filter.radius,
+        ];
+        for v in [config.look_from, config.look_at, config.v_up] {
+            numbers.extend([v.x(), v.y(), v.z()]);
+        }
+        match config.adaptive {
+            Some(adaptive) => {
+                numbers.extend([1.0, adaptive.threshold, adaptive.min_samples as f64])
+            }
+            None => numbers.push(0.0),
+        }
+        for n in numbers {
+            data.extend_from_slice(&n.to_le_bytes());
+        }
+        for name in [config.sampler.name(), config.filter.kind.name()] {
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+        }
+        Ok(fingerprint(&data))
+    }
+
+    pub fn apply(&self, config: &mut CameraConfig) {
+        // Command line settings override the ones in the scene.
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => {
+                config.image_width = width as f64;
+                config.aspect_ratio = width as f64 / height as f64;
+            }
+            (Some(width), None) => config.image_width = width as f64,
+            (None, Some(height)) => {
+                config.image_width = (height as f64 * config.aspect_ratio).round().max(1.0);
+                config.aspect_ratio = config.image_width / height as f64;
+            }
+            (None, None) => {}
+        }
+        if let Some(spp) = self.samples_per_pixel {
+            config.samples_per_pixel = spp as f64;
+        }
+        if let Some(max_depth) = self.max_depth {
+            config.max_depth = max_depth;
+        }
+        if let Some(seed) = self.seed {
+            config.seed = seed;
+        }
+        if let Some(threshold) = self.adaptive {
+            let min_samples: u32 = config
+                .adaptive
+                .map_or(AdaptiveSampling::DEFAULT_MIN_SAMPLES, |a| a.min_samples);
+            config.adaptive = Some(AdaptiveSampling {
+                threshold,
+                min_samples,
+            });
+        }
+        if let (Some(min_samples), Some(adaptive)) = (self.min_spp, config.adaptive.as_mut()) {
+            adaptive.min_samples = min_samples;
+        }
+        if let Some(sampler) = self.sampler {
+            config.sampler = sampler.into();
+        }
+        if let Some(filter) = self.filter {
+            config.filter = Filter::new(filter.into());
+        }
+        if let Some(radius) = self.filter_radius {
+            config.filter.radius = radius;
+        }
+        config.tiles.size = self.tile_size as usize;
+        config.tiles.order = self.tile_order.into();
+    }
+}
+
+fn parse_positive(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(v) if v > 0.0 && v.is_finite() => Ok(v),
+        _ => Err(format!("'{s}' is not a positive number")),
+    }
+}
@@ -1,31 +1,29 @@
-use crate::{camera::string_to_u8, interval::Interval, vec3::Vec3};
-use std::io::Write;
+use crate::{interval::Interval, tonemap::ToneMapping, vec3::Vec3};
 
 pub type Color = Vec3;
 
-fn linear_to_gamma(linear_component: f64) -> f64 {
-    match linear_component {
-        x if x > 0.0 => linear_component.sqrt(),
-        _ => 0.0,
-    }
+pub fn luminance(c: Color) -> f64 {
+    // Rec. 709 / sRGB luminance weights.
+    0.2126 * c.x() + 0.7152 * c.y() + 0.0722 * c.z()
 }
 
-static INTENSITY: Interval = Interval::new(0.0, 0.999);
-pub fn build_color(pixel_color: Color) -> [u8; 11] {
-    // Apply a linear to gamma transform for gamma 2
-    let r: f64 = linear_to_gamma(pixel_color.x());
-    let g: f64 = linear_to_gamma(pixel_color.y());
-    let b: f64 = linear_to_gamma(pixel_color.z());
-
-    // Translate the [0, 1] component values to the byte range [0, 25]
-    let r_byte: i64 = (255.999 * INTENSITY.clamp(r)) as i64;
-    let g_byte: i64 = (255.999 * INTENSITY.clamp(g)) as i64;
-    let b_byte: i64 = (255.999 * INTENSITY.clamp(b)) as i64;
-
-    let color: String = format!("{} {} {}", r_byte, g_byte, b_byte);
-    string_to_u8(&color)
+fn linear_to_srgb(linear_component: f64) -> f64 {
+    // The piecewise sRGB transfer function (IEC 61966-2-1): a linear toe near black, then a
+    // 1/2.4 power curve.
+    match linear_component {
+        x if x <= 0.0 => 0.0,
+        x if x <= 0.0031308 => 12.92 * x,
+        x => 1.055 * x.powf(1.0 / 2.4) - 0.055,
+    }
 }
 
-pub fn write_color(out: &mut impl Write, pixel_color: String) {
-    writeln!(out, "{}", pixel_color).expect("to write ppm metadata");
+static UNIT: Interval = Interval::new(0.0, 1.0);
+pub fn build_color(pixel_color: Color, tone_mapping: &ToneMapping, max_value: u16) -> [u16; 3] {
+    // Tone map the linear radiance into [0, 1], apply the sRGB transfer function, then translate
+    // the component values to the integer range [0, max_value]
+    let scale: f64 = max_value as f64 + 1.0;
+    tone_mapping
+        .apply(pixel_color)
+        .e
+        .map(|c| ((scale * UNIT.clamp(linear_to_srgb(c))) as u16).min(max_value))
 }
@@ -0,0 +1,96 @@
+// Piecewise-constant probability distribution over [0, 1), proportional to a tabulated function,
+// for importance sampling. A function that is zero everywhere falls back to a uniform one.
+#[derive(Clone, Debug)]
+pub struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    pub fn new(func: Vec<f64>) -> Self {
+        let n: usize = func.len();
+        let mut cdf: Vec<f64> = Vec::with_capacity(n + 1);
+        cdf.push(0.0);
+        for (i, f) in func.iter().enumerate() {
+            cdf.push(cdf[i] + f / n as f64);
+        }
+        let integral: f64 = cdf[n];
+        for (i, c) in cdf.iter_mut().enumerate() {
+            *c = match integral > 0.0 {
+                true => *c / integral,
+                false => i as f64 / n as f64,
+            };
+        }
+        Self {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    // Integral of the function over [0, 1).
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    // Maps a uniform sample to a point in [0, 1), returning it with its density and the index of
+    // the piece it fell in.
+    pub fn sample(&self, u: f64) -> (f64, f64, usize) {
+        let n: usize = self.func.len();
+        let index: usize = (self.cdf.partition_point(|&c| c <= u) - 1).min(n - 1);
+        let width: f64 = self.cdf[index + 1] - self.cdf[index];
+        let offset: f64 = match width > 0.0 {
+            true => (u - self.cdf[index]) / width,
+            false => 0.0,
+        };
+        let x: f64 = ((index as f64 + offset) / n as f64).min(1.0 - f64::EPSILON / 2.0);
+        (x, self.pdf(index), index)
+    }
+
+    // Density of the samples in piece `index`.
+    pub fn pdf(&self, index: usize) -> f64 {
+        match self.integral > 0.0 {
+            true => self.func[index] / self.integral,
+            false => 1.0,
+        }
+    }
+
+    fn index(&self, x: f64) -> usize {
+        ((x * self.func.len() as f64) as usize).min(self.func.len() - 1)
+    }
+}
+
+// Piecewise-constant distribution over [0, 1)^2 proportional to a `width` x `height` table,
+// sampled as a row from the marginal distribution, then a column within the row.
+#[derive(Clone, Debug)]
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    // `func` is row-major, `width` values per row.
+    pub fn new(func: &[f64], width: usize) -> Self {
+        let rows: Vec<Distribution1D> = func
+            .chunks(width)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal: Distribution1D =
+            Distribution1D::new(rows.iter().map(|r| r.integral).collect());
+        Self { rows, marginal }
+    }
+
+    // Maps a pair of uniform samples to a point (x, y), returning it with its density.
+    pub fn sample(&self, (u, v): (f64, f64)) -> ((f64, f64), f64) {
+        let (y, pdf_y, row) = self.marginal.sample(v);
+        let (x, pdf_x, _) = self.rows[row].sample(u);
+        ((x, y), pdf_x * pdf_y)
+    }
+
+    pub fn pdf(&self, x: f64, y: f64) -> f64 {
+        let row: usize = self.marginal.index(y);
+        let row_distribution: &Distribution1D = &self.rows[row];
+        self.marginal.pdf(row) * row_distribution.pdf(row_distribution.index(x))
+    }
+}
@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+
+// Shape of the reconstruction filter that weights each camera sample's contribution to the
+// pixels around it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FilterKind {
+    // Equal weight for every sample within the radius; with the default radius of half a pixel
+    // each sample only counts towards the pixel it was taken in.
+    #[default]
+    Box,
+    // Weight falling off linearly to zero at the radius.
+    Tent,
+    // Gaussian with a standard deviation of a third of the radius, shifted to reach zero there.
+    Gaussian,
+    // Mitchell-Netravali cubic with B = C = 1/3, stretched over the radius.
+    Mitchell,
+    // Sinc windowed by a sinc stretched over the radius, as many lobes as the radius is wide.
+    Lanczos,
+}
+
+impl FilterKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "box" => Some(Self::Box),
+            "tent" => Some(Self::Tent),
+            "gaussian" => Some(Self::Gaussian),
+            "mitchell" => Some(Self::Mitchell),
+            "lanczos" => Some(Self::Lanczos),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Box => "box",
+            Self::Tent => "tent",
+            Self::Gaussian => "gaussian",
+            Self::Mitchell => "mitchell",
+            Self::Lanczos => "lanczos",
+        }
+    }
+
+    // Radius in pixels used when none is given.
+    pub fn default_radius(self) -> f64 {
+        match self {
+            Self::Box => 0.5,
+            Self::Tent => 1.0,
+            Self::Gaussian => 1.5,
+            Self::Mitchell => 2.0,
+            Self::Lanczos => 3.0,
+        }
+    }
+}
+
+// Separable reconstruction filter: a sample at offset (dx, dy) pixels from a pixel's centre adds
+// to that pixel with weight f(dx) f(dy), and every pixel is the weighted mean of the samples
+// within `radius` of it. Mitchell and Lanczos have negative lobes, which sharpen edges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    pub radius: f64,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(FilterKind::default())
+    }
+}
+
+impl Filter {
+    pub fn new(kind: FilterKind) -> Self {
+        Self {
+            kind,
+            radius: kind.default_radius(),
+        }
+    }
+
+    pub fn with_radius(kind: FilterKind, radius: f64) -> Self {
+        Self { kind, radius }
+    }
+
+    // How many pixels beyond its own, on each side, a sample can reach.
+    pub fn margin(&self) -> usize {
+        (self.radius + 0.5) as usize
+    }
+
+    pub fn evaluate(&self, dx: f64, dy: f64) -> f64 {
+        self.evaluate_1d(dx) * self.evaluate_1d(dy)
+    }
+
+    fn evaluate_1d(&self, d: f64) -> f64 {
+        let (x, r) = (d.abs(), self.radius);
+        if x > r {
+            return 0.0;
+        }
+        match self.kind {
+            FilterKind::Box => 1.0,
+            FilterKind::Tent => 1.0 - x / r,
+            FilterKind::Gaussian => {
+                let sigma: f64 = r / 3.0;
+                let gaussian = |x: f64| (-x * x / (2.0 * sigma * sigma)).exp();
+                gaussian(x) - gaussian(r)
+            }
+            FilterKind::Mitchell => mitchell(2.0 * x / r),
+            FilterKind::Lanczos => sinc(x) * sinc(x / r),
+        }
+    }
+}
+
+fn mitchell(x: f64) -> f64 {
+    const B: f64 = 1.0 / 3.0;
+    const C: f64 = 1.0 / 3.0;
+    let value: f64 = match x {
+        x if x < 1.0 => {
+            (12.0 - 9.0 * B - 6.0 * C) * x * x * x
+                + (-18.0 + 12.0 * B + 6.0 * C) * x * x
+                + (6.0 - 2.0 * B)
+        }
+        x if x < 2.0 => {
+            (-B - 6.0 * C) * x * x * x
+                + (6.0 * B + 30.0 * C) * x * x
+                + (-12.0 * B - 48.0 * C) * x
+                + (8.0 * B + 24.0 * C)
+        }
+        _ => 0.0,
+    };
+    value / 6.0
+}
+
+fn sinc(x: f64) -> f64 {
+    match x.abs() < 1e-5 {
+        true => 1.0,
+        false => (PI * x).sin() / (PI * x),
+    }
+}
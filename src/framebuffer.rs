@@ -0,0 +1,44 @@
+use crate::color::Color;
+
+// Linear RGB radiance for every pixel of an image, stored row-major with the top row first.
+#[derive(Clone, Debug)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 3]>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 3]; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        let [r, g, b] = self.pixels[y * self.width + x];
+        Color::new(r as f64, g as f64, b as f64)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        self.pixels[y * self.width + x] = color.e.map(|c| c as f32);
+    }
+
+    pub fn pixels(&self) -> &[[f32; 3]] {
+        &self.pixels
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, [f32; 3]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+}
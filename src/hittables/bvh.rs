@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use super::{
+    hittable::{HitRecord, Hittable, HittableType},
+    hittable_list::HittableList,
+};
+use crate::{aabb::Aabb, interval::Interval, ray::Ray};
+
+// Number of centroid buckets evaluated per split by the surface area heuristic.
+const SAH_BUCKET_COUNT: usize = 12;
+
+pub struct BvhNode {
+    left: Arc<HittableType>,
+    right: Arc<HittableType>,
+    bbox: Aabb,
+}
+
+impl BvhNode {
+    pub fn new(list: HittableList) -> Self {
+        let mut objects: Vec<Arc<HittableType>> = list.objects;
+        if objects.is_empty() {
+            // Nothing to split, so fall back to a node over a single empty list.
+            let empty: Arc<HittableType> = Arc::new(HittableType::List(Arc::default()));
+            return Self {
+                left: empty.clone(),
+                right: empty,
+                bbox: Aabb::EMPTY,
+            };
+        }
+        Self::build(&mut objects)
+    }
+
+    // The two subtrees; both are the same object in a node over a single object.
+    pub fn children(&self) -> (&Arc<HittableType>, &Arc<HittableType>) {
+        (&self.left, &self.right)
+    }
+
+    fn build(objects: &mut [Arc<HittableType>]) -> Self {
+        // Build the bounding box of the span of source objects.
+        let bbox: Aabb = objects.iter().fold(Aabb::EMPTY, |acc, object| {
+            Aabb::surrounding(&acc, &object.bounding_box())
+        });
+
+        let (left, right) = match objects.len() {
+            1 => (objects[0].clone(), objects[0].clone()),
+            2 => (objects[0].clone(), objects[1].clone()),
+            _ => {
+                let mid: usize = Self::partition(objects);
+                let (lower, upper) = objects.split_at_mut(mid);
+                (Self::wrap(lower), Self::wrap(upper))
+            }
+        };
+
+        Self { left, right, bbox }
+    }
+
+    fn wrap(objects: &mut [Arc<HittableType>]) -> Arc<HittableType> {
+        // Avoid an extra level of indirection for single-object spans.
+        match objects.len() {
+            1 => objects[0].clone(),
+            _ => Arc::new(HittableType::Bvh(Arc::new(Self::build(objects)))),
+        }
+    }
+
+    fn partition(objects: &mut [Arc<HittableType>]) -> usize {
+        // Reorders `objects` along the longest centroid axis and returns the split index chosen
+        // by a bucketed surface area heuristic, falling back to a median split.
+        let centroid_bounds: Aabb = objects.iter().fold(Aabb::EMPTY, |acc, object| {
+            let c = object.bounding_box().centroid();
+            Aabb::surrounding(&acc, &Aabb::from_points(c, c))
+        });
+        let axis: usize = centroid_bounds.longest_axis();
+        let axis_bounds: Interval = centroid_bounds.axis_interval(axis);
+        let centroid_of = |object: &Arc<HittableType>| object.bounding_box().centroid().e[axis];
+
+        objects.sort_unstable_by(|a, b| centroid_of(a).total_cmp(&centroid_of(b)));
+        let median: usize = objects.len() / 2;
+        if axis_bounds.size() <= 0.0 {
+            return median;
+        }
+
+        let bucket_of = |object: &Arc<HittableType>| {
+            let offset: f64 = (centroid_of(object) - axis_bounds.min) / axis_bounds.size();
+            ((offset * SAH_BUCKET_COUNT as f64) as usize).min(SAH_BUCKET_COUNT - 1)
+        };
+
+        let mut counts = [0usize; SAH_BUCKET_COUNT];
+        let mut bounds = [Aabb::EMPTY; SAH_BUCKET_COUNT];
+        for object in objects.iter() {
+            let b: usize = bucket_of(object);
+            counts[b] += 1;
+            bounds[b] = Aabb::surrounding(&bounds[b], &object.bounding_box());
+        }
+
+        // Evaluate the cost of splitting after each bucket; the relative cost only needs the
+        // child surface areas weighted by their primitive counts.
+        let mut best_cost: f64 = f64::INFINITY;
+        let mut best_split: usize = 0;
+        for split in 0..SAH_BUCKET_COUNT - 1 {
+            let (below, above) = (&counts[..=split], &counts[split + 1..]);
+            let count_below: usize = below.iter().sum();
+            let count_above: usize = above.iter().sum();
+            if count_below == 0 || count_above == 0 {
+                continue;
+            }
+            let box_below: Aabb = bounds[..=split]
+                .iter()
+                .fold(Aabb::EMPTY, |acc, b| Aabb::surrounding(&acc, b));
+            let box_above: Aabb = bounds[split + 1..]
+                .iter()
+                .fold(Aabb::EMPTY, |acc, b| Aabb::surrounding(&acc, b));
+            let cost: f64 = count_below as f64 * box_below.surface_area()
+                + count_above as f64 * box_above.surface_area();
+            if cost < best_cost {
+                best_cost = cost;
+                best_split = count_below;
+            }
+        }
+
+        match best_split {
+            0 => median,
+            split => split,
+        }
+    }
+}
+
+impl<'a> Hittable<'a> for BvhNode {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &'a mut HitRecord) -> bool {
+        if !self.bbox.hit(r, ray_t) {
+            return false;
+        }
+
+        let hit_left: bool = self.left.hit(r, ray_t, rec);
+        let right_t: Interval = match hit_left {
+            true => Interval::new(ray_t.min, rec.t),
+            false => *ray_t,
+        };
+        let hit_right: bool = self.right.hit(r, &right_t, rec);
+
+        hit_left || hit_right
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
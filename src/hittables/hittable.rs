@@ -1,7 +1,12 @@
 use std::sync::Arc;
 
-use super::{hittable_list::HittableList, sphere::Sphere};
+use super::{
+    bvh::BvhNode, hittable_list::HittableList, mesh::TriangleMesh, sphere::Sphere,
+    triangle::Triangle,
+};
 use crate::{
+    aabb::Aabb,
+    color::Color,
     interval::Interval,
     materials::material::Material,
     ray::{Point3, Ray},
@@ -12,8 +17,13 @@ use crate::{
 pub struct HitRecord {
     pub p: Point3,
     pub normal: Vec3,
+    pub geometric_normal: Vec3,
     pub t: f64,
-    pub mat: Arc<Material>, // Use Arc to make Material thread-safe
+    pub u: f64,
+    pub v: f64,
+    pub mat: Arc<Material>,   // Use Arc to make Material thread-safe
+    pub color: Option<Color>, // Interpolated vertex color, for meshes that carry them
+    pub shape: usize,         // Address of the sphere or triangle hit, to tell which one it was
     pub front_face: bool,
 }
 
@@ -27,16 +37,31 @@ impl HitRecord {
             true => *outward_normal,
             false => -*outward_normal,
         };
+        self.geometric_normal = self.normal;
+    }
+
+    pub fn set_shading_normal(&mut self, shading_normal: &Vec3) {
+        // Overrides the shading normal while keeping it on the same side as the geometric normal.
+        // NOTE: call after `set_face_normal`; `shading_normal` is assumed to have unit length.
+
+        self.normal = match dot(*shading_normal, self.geometric_normal) < 0.0 {
+            true => -*shading_normal,
+            false => *shading_normal,
+        };
     }
 }
 
 pub enum HittableType {
     Sphere(Arc<Sphere>),     // Use Arc to make Sphere thread-safe
     List(Arc<HittableList>), // Use Arc for thread-safe HittableList
+    Bvh(Arc<BvhNode>),
+    Triangle(Arc<Triangle>),
+    Mesh(Arc<TriangleMesh>),
 }
 
 pub trait Hittable<'a>: Send + Sync {
     fn hit(&self, r: &Ray, ray_t: &Interval, rec: &'a mut HitRecord) -> bool;
+    fn bounding_box(&self) -> Aabb;
 }
 
 impl HittableType {
@@ -44,6 +69,19 @@ impl HittableType {
         match self {
             Self::List(l) => l.hit(r, ray_t, rec),
             Self::Sphere(s) => s.hit(r, ray_t, rec),
+            Self::Bvh(b) => b.hit(r, ray_t, rec),
+            Self::Triangle(t) => t.hit(r, ray_t, rec),
+            Self::Mesh(m) => m.hit(r, ray_t, rec),
+        }
+    }
+
+    pub fn bounding_box(&self) -> Aabb {
+        match self {
+            Self::List(l) => l.bounding_box(),
+            Self::Sphere(s) => s.bounding_box(),
+            Self::Bvh(b) => b.bounding_box(),
+            Self::Triangle(t) => t.bounding_box(),
+            Self::Mesh(m) => m.bounding_box(),
         }
     }
 }
@@ -1,26 +1,29 @@
 use std::sync::Arc;
 
 use super::hittable::{HitRecord, Hittable, HittableType};
-use crate::interval::Interval;
+use crate::{aabb::Aabb, interval::Interval};
 
 #[derive(Default)]
 pub struct HittableList {
     pub objects: Vec<Arc<HittableType>>, // Use Arc for thread safety
+    bbox: Aabb,
 }
 
 impl HittableList {
     pub fn new(object: HittableType) -> Self {
-        Self {
-            objects: vec![Arc::new(object)], // Wrap object in Arc
-        }
+        let mut list = Self::default();
+        list.add(object);
+        list
     }
 
     pub fn add(&mut self, object: HittableType) {
+        self.bbox = Aabb::surrounding(&self.bbox, &object.bounding_box());
         self.objects.push(Arc::new(object)); // Wrap object in Arc
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = Aabb::EMPTY;
     }
 }
 
@@ -31,14 +34,7 @@ impl<'a> Hittable<'a> for HittableList {
         let mut closest_so_far = ray_t.max;
 
         for object in &self.objects {
-            if match object.as_ref() {
-                HittableType::Sphere(s) => {
-                    s.hit(r, &Interval::new(ray_t.min, closest_so_far), &mut temp_rec)
-                }
-                HittableType::List(l) => {
-                    l.hit(r, &Interval::new(ray_t.min, closest_so_far), &mut temp_rec)
-                }
-            } {
+            if object.hit(r, &Interval::new(ray_t.min, closest_so_far), &mut temp_rec) {
                 hit_anything = true;
                 closest_so_far = temp_rec.t;
                 *rec = temp_rec.clone();
@@ -47,5 +43,8 @@ impl<'a> Hittable<'a> for HittableList {
 
         hit_anything
     }
-}
 
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use super::{
+    bvh::BvhNode,
+    hittable::{HitRecord, Hittable, HittableType},
+    hittable_list::HittableList,
+    triangle::Triangle,
+};
+use crate::{
+    aabb::Aabb,
+    color::Color,
+    interval::Interval,
+    materials::material::Material,
+    ray::{Point3, Ray},
+    vec3::Vec3,
+};
+
+// Vertex attribute buffers shared by every triangle of a mesh.
+#[derive(Default, Debug)]
+pub struct MeshBuffers {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub texcoords: Vec<(f64, f64)>,
+    pub colors: Vec<Color>, // Per-position vertex colors, if the source provides them
+}
+
+// Indices of one triangle corner into the `MeshBuffers` attribute arrays.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MeshVertex {
+    pub position: usize,
+    pub normal: Option<usize>,
+    pub texcoord: Option<usize>,
+}
+
+impl MeshVertex {
+    pub fn new(position: usize) -> Self {
+        Self {
+            position,
+            normal: None,
+            texcoord: None,
+        }
+    }
+}
+
+pub struct MeshFace {
+    pub vertices: [MeshVertex; 3],
+    pub mat: Arc<Material>,
+}
+
+pub struct TriangleMesh {
+    buffers: Arc<MeshBuffers>,
+    triangle_count: usize,
+    bvh: BvhNode,
+}
+
+impl TriangleMesh {
+    pub fn new(buffers: MeshBuffers, faces: Vec<MeshFace>) -> Self {
+        let buffers: Arc<MeshBuffers> = Arc::new(buffers);
+        let triangle_count: usize = faces.len();
+        let mut triangles: HittableList = HittableList::default();
+        for face in faces {
+            triangles.add(HittableType::Triangle(Arc::new(Triangle::from_mesh(
+                buffers.clone(),
+                face.vertices,
+                face.mat,
+            ))));
+        }
+
+        Self {
+            buffers,
+            triangle_count,
+            bvh: BvhNode::new(triangles),
+        }
+    }
+
+    pub fn buffers(&self) -> &MeshBuffers {
+        &self.buffers
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangle_count
+    }
+
+    pub fn bvh(&self) -> &BvhNode {
+        &self.bvh
+    }
+}
+
+impl<'a> Hittable<'a> for TriangleMesh {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &'a mut HitRecord) -> bool {
+        self.bvh.hit(r, ray_t, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
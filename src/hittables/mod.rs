@@ -1,3 +1,6 @@
+pub mod bvh;
 pub mod hittable;
 pub mod hittable_list;
+pub mod mesh;
 pub mod sphere;
+pub mod triangle;
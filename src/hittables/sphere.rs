@@ -1,27 +1,76 @@
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
 use super::hittable::{HitRecord, Hittable};
 use crate::{
+    aabb::Aabb,
     interval::Interval,
     materials::material::Material,
     ray::{Point3, Ray},
-    vec3::{dot, Vec3},
+    vec3::{dot, sample_cone, unit_vector, Vec3},
 };
 
 pub struct Sphere {
     center: Point3,
     radius: f64,
     mat: Arc<Material>, // Use Arc instead of Rc for thread safety
+    bbox: Aabb,
 }
 
 impl Sphere {
     pub fn new(center: &Point3, radius: f64, mat: Material) -> Self {
+        let radius: f64 = radius.max(0.0);
+        let rvec: Vec3 = Vec3::new(radius, radius, radius);
         Self {
             center: *center,
-            radius: radius.max(0.0),
+            radius,
             mat: Arc::new(mat), // Wrap Material in an Arc
+            bbox: Aabb::from_points(*center - rvec, *center + rvec),
         }
     }
+
+    pub fn material(&self) -> &Material {
+        &self.mat
+    }
+
+    pub fn area(&self) -> f64 {
+        4.0 * PI * self.radius * self.radius
+    }
+
+    // Picks a direction from `origin` towards the sphere, uniformly within the cone it subtends,
+    // returning it with its density per unit solid angle. None from inside the sphere.
+    pub fn sample_direction(&self, origin: Point3, u: (f64, f64)) -> Option<(Vec3, f64)> {
+        let cos_max: f64 = self.cos_subtended(origin)?;
+        let direction: Vec3 = sample_cone(unit_vector(self.center - origin), cos_max, u);
+        Some((direction, 1.0 / (2.0 * PI * (1.0 - cos_max))))
+    }
+
+    // Density per unit solid angle with which `sample_direction` picks a direction from `origin`
+    // that hits the sphere.
+    pub fn direction_pdf(&self, origin: Point3) -> f64 {
+        match self.cos_subtended(origin) {
+            Some(cos_max) => 1.0 / (2.0 * PI * (1.0 - cos_max)),
+            None => 0.0,
+        }
+    }
+
+    // Cosine of the half-angle of the cone the sphere subtends from `origin`, if outside it.
+    fn cos_subtended(&self, origin: Point3) -> Option<f64> {
+        let distance_squared: f64 = (self.center - origin).length_squared();
+        let sin2_max: f64 = self.radius * self.radius / distance_squared;
+        match sin2_max < 1.0 {
+            true => Some((1.0 - sin2_max).sqrt()).filter(|&cos_max| cos_max < 1.0),
+            false => None,
+        }
+    }
+
+    fn get_sphere_uv(p: &Point3) -> (f64, f64) {
+        // p: a given point on the sphere of radius one, centered at the origin.
+        // u: returned value [0,1] of angle around the Y axis from X=-1.
+        // v: returned value [0,1] of angle from Y=-1 to Y=+1.
+        let theta: f64 = (-p.y()).acos();
+        let phi: f64 = (-p.z()).atan2(p.x()) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
 }
 
 impl<'a> Hittable<'a> for Sphere {
@@ -48,10 +97,16 @@ impl<'a> Hittable<'a> for Sphere {
         rec.t = root;
         rec.p = r.at(rec.t);
         rec.mat = self.mat.clone(); // Clone the material reference
+        rec.color = None;
+        rec.shape = self as *const Self as usize;
         let outward_normal: Vec3 = (rec.p - self.center) / self.radius;
         rec.set_face_normal(r, &outward_normal);
+        (rec.u, rec.v) = Self::get_sphere_uv(&outward_normal);
 
         true
     }
-}
 
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
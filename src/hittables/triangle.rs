@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use super::{
+    hittable::{HitRecord, Hittable},
+    mesh::{MeshBuffers, MeshVertex},
+};
+use crate::{
+    aabb::Aabb,
+    color::Color,
+    interval::Interval,
+    materials::material::Material,
+    ray::{Point3, Ray},
+    vec3::{cross, dot, unit_vector, Vec3},
+};
+
+pub struct Triangle {
+    buffers: Arc<MeshBuffers>, // Shared with the other triangles of the same mesh
+    vertices: [MeshVertex; 3],
+    mat: Arc<Material>,
+    normal: Vec3,
+    bbox: Aabb,
+}
+
+impl Triangle {
+    pub fn new(p0: &Point3, p1: &Point3, p2: &Point3, mat: Material) -> Self {
+        let buffers: MeshBuffers = MeshBuffers {
+            positions: vec![*p0, *p1, *p2],
+            ..Default::default()
+        };
+        let vertices: [MeshVertex; 3] = [0, 1, 2].map(MeshVertex::new);
+        Self::from_mesh(Arc::new(buffers), vertices, Arc::new(mat))
+    }
+
+    pub fn with_normals(vertices: [Point3; 3], normals: [Vec3; 3], mat: Material) -> Self {
+        let buffers: MeshBuffers = MeshBuffers {
+            positions: vertices.to_vec(),
+            normals: normals.to_vec(),
+            ..Default::default()
+        };
+        let vertices: [MeshVertex; 3] = [0, 1, 2].map(|i| MeshVertex {
+            normal: Some(i),
+            ..MeshVertex::new(i)
+        });
+        Self::from_mesh(Arc::new(buffers), vertices, Arc::new(mat))
+    }
+
+    pub fn from_mesh(
+        buffers: Arc<MeshBuffers>,
+        vertices: [MeshVertex; 3],
+        mat: Arc<Material>,
+    ) -> Self {
+        let [p0, p1, p2] = vertices.map(|v| buffers.positions[v.position]);
+        let bbox: Aabb = Aabb::surrounding(&Aabb::from_points(p0, p1), &Aabb::from_points(p2, p2));
+        Self {
+            buffers,
+            vertices,
+            mat,
+            normal: unit_vector(cross(p1 - p0, p2 - p0)),
+            bbox,
+        }
+    }
+
+    pub fn material(&self) -> &Material {
+        &self.mat
+    }
+
+    pub fn area(&self) -> f64 {
+        let [p0, p1, p2] = self.positions();
+        0.5 * cross(p1 - p0, p2 - p0).length()
+    }
+
+    // Picks a direction from `origin` towards a point uniformly distributed over the triangle,
+    // returning it with its density per unit solid angle.
+    pub fn sample_direction(&self, origin: Point3, (u, v): (f64, f64)) -> Option<(Vec3, f64)> {
+        let [p0, p1, p2] = self.positions();
+        let su: f64 = u.sqrt();
+        let point: Point3 = (1.0 - su) * p0 + (su * (1.0 - v)) * p1 + (su * v) * p2;
+        let pdf: f64 = self.direction_pdf(origin, point);
+        match pdf > 0.0 && pdf.is_finite() {
+            true => Some((unit_vector(point - origin), pdf)),
+            false => None,
+        }
+    }
+
+    // Density per unit solid angle with which `sample_direction` picks the direction from
+    // `origin` through `point` on the triangle.
+    pub fn direction_pdf(&self, origin: Point3, point: Point3) -> f64 {
+        let to_point: Vec3 = point - origin;
+        let cosine: f64 = dot(self.normal, unit_vector(to_point)).abs();
+        match cosine > 0.0 {
+            true => to_point.length_squared() / (self.area() * cosine),
+            false => 0.0,
+        }
+    }
+
+    fn positions(&self) -> [Point3; 3] {
+        self.vertices.map(|v| self.buffers.positions[v.position])
+    }
+
+    fn interpolated_normal(&self, weights: &[f64; 3]) -> Option<Vec3> {
+        // Only smooth-shade when every corner carries a vertex normal.
+        let mut n: Vec3 = Vec3::default();
+        for (vertex, weight) in self.vertices.iter().zip(weights) {
+            n += *weight * self.buffers.normals[vertex.normal?];
+        }
+        match n.near_zero() {
+            true => None,
+            false => Some(unit_vector(n)),
+        }
+    }
+
+    fn interpolated_color(&self, weights: &[f64; 3]) -> Option<Color> {
+        if self.buffers.colors.is_empty() {
+            return None;
+        }
+        let mut color: Color = Color::default();
+        for (vertex, weight) in self.vertices.iter().zip(weights) {
+            color += *weight * self.buffers.colors[vertex.position];
+        }
+        Some(color)
+    }
+
+    fn interpolated_texcoord(&self, weights: &[f64; 3]) -> Option<(f64, f64)> {
+        let mut uv: (f64, f64) = (0.0, 0.0);
+        for (vertex, weight) in self.vertices.iter().zip(weights) {
+            let (u, v) = self.buffers.texcoords[vertex.texcoord?];
+            uv.0 += weight * u;
+            uv.1 += weight * v;
+        }
+        Some(uv)
+    }
+}
+
+// Watertight ray/triangle intersection (Woop, Benthin and Wald, JCGT 2013). Returns the ray
+// parameter and the barycentric weights of the three vertices. Edges shared between triangles
+// are evaluated identically from both sides, so rays cannot slip through a closed mesh.
+pub fn intersect_triangle(
+    r: &Ray,
+    ray_t: &Interval,
+    vertices: &[Point3; 3],
+) -> Option<(f64, [f64; 3])> {
+    let dir: Vec3 = r.direction();
+
+    // Pick the dominant ray direction axis as z, and keep the winding of the remaining two.
+    let abs_dir: [f64; 3] = dir.e.map(f64::abs);
+    let kz: usize = match abs_dir {
+        [x, y, z] if x > y && x > z => 0,
+        [_, y, z] if y > z => 1,
+        _ => 2,
+    };
+    let (kx, ky) = match dir.e[kz] < 0.0 {
+        true => ((kz + 2) % 3, (kz + 1) % 3),
+        false => ((kz + 1) % 3, (kz + 2) % 3),
+    };
+
+    // Shear and scale so the ray points down the +z axis with unit length.
+    let sx: f64 = dir.e[kx] / dir.e[kz];
+    let sy: f64 = dir.e[ky] / dir.e[kz];
+    let sz: f64 = 1.0 / dir.e[kz];
+
+    let [a, b, c] = vertices.map(|p| p - r.origin());
+    let (ax, ay) = (a.e[kx] - sx * a.e[kz], a.e[ky] - sy * a.e[kz]);
+    let (bx, by) = (b.e[kx] - sx * b.e[kz], b.e[ky] - sy * b.e[kz]);
+    let (cx, cy) = (c.e[kx] - sx * c.e[kz], c.e[ky] - sy * c.e[kz]);
+
+    // Scaled barycentric coordinates from the 2D edge functions.
+    let u: f64 = cx * by - cy * bx;
+    let v: f64 = ax * cy - ay * cx;
+    let w: f64 = bx * ay - by * ax;
+    if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+        return None;
+    }
+
+    let det: f64 = u + v + w;
+    if det == 0.0 {
+        return None;
+    }
+
+    let t_scaled: f64 = u * sz * a.e[kz] + v * sz * b.e[kz] + w * sz * c.e[kz];
+    let t: f64 = t_scaled / det;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+
+    Some((t, [u / det, v / det, w / det]))
+}
+
+impl<'a> Hittable<'a> for Triangle {
+    fn hit(&self, r: &Ray, ray_t: &Interval, rec: &'a mut HitRecord) -> bool {
+        let positions: [Point3; 3] = self.positions();
+        let Some((t, weights)) = intersect_triangle(r, ray_t, &positions) else {
+            return false;
+        };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        // Fall back to barycentric coordinates when the mesh has no texture coordinates.
+        (rec.u, rec.v) = self
+            .interpolated_texcoord(&weights)
+            .unwrap_or((weights[1], weights[2]));
+        rec.mat = self.mat.clone();
+        rec.color = self.interpolated_color(&weights);
+        rec.shape = self as *const Self as usize;
+        rec.set_face_normal(r, &self.normal);
+        if let Some(shading_normal) = self.interpolated_normal(&weights) {
+            rec.set_shading_normal(&shading_normal);
+        }
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{color::build_color, framebuffer::Framebuffer, tonemap::ToneMapping};
+
+// Display-ready RGB image with integer samples in [0, max_value], row-major with the top row
+// first. Produced from a linear `Framebuffer` for the low dynamic range image formats.
+#[derive(Clone, Debug)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    max_value: u16,
+    samples: Vec<u16>,
+}
+
+impl Image {
+    pub fn from_framebuffer(
+        framebuffer: &Framebuffer,
+        tone_mapping: &ToneMapping,
+        max_value: u16,
+    ) -> Self {
+        let samples: Vec<u16> = (0..framebuffer.height())
+            .flat_map(|j| (0..framebuffer.width()).map(move |i| (i, j)))
+            .flat_map(|(i, j)| build_color(framebuffer.get(i, j), tone_mapping, max_value))
+            .collect();
+        Self {
+            width: framebuffer.width(),
+            height: framebuffer.height(),
+            max_value,
+            samples,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn max_value(&self) -> u16 {
+        self.max_value
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> [u16; 3] {
+        let idx: usize = 3 * (y * self.width + x);
+        [
+            self.samples[idx],
+            self.samples[idx + 1],
+            self.samples[idx + 2],
+        ]
+    }
+
+    // Interleaved R, G, B samples of every pixel.
+    pub fn samples(&self) -> &[u16] {
+        &self.samples
+    }
+}
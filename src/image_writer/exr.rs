@@ -0,0 +1,96 @@
+use std::io::{self, Write};
+
+use half::f16;
+
+use super::BitDepth;
+use crate::framebuffer::Framebuffer;
+
+// OpenEXR pixel type codes.
+const PIXEL_TYPE_HALF: i32 = 1;
+const PIXEL_TYPE_FLOAT: i32 = 2;
+
+// Writes a single-part, uncompressed scanline OpenEXR image with half (16-bit) or float
+// (32-bit) R, G and B channels.
+pub fn write_exr(out: &mut impl Write, image: &Framebuffer, bit_depth: BitDepth) -> io::Result<()> {
+    let (pixel_type, sample_size) = match bit_depth {
+        BitDepth::ThirtyTwo => (PIXEL_TYPE_FLOAT, 4),
+        _ => (PIXEL_TYPE_HALF, 2),
+    };
+    let (width, height) = (image.width(), image.height());
+    let (x_max, y_max) = (width as i32 - 1, height as i32 - 1);
+
+    let mut header: Vec<u8> = Vec::new();
+    header.extend_from_slice(&[0x76, 0x2f, 0x31, 0x01]); // Magic number
+    header.extend_from_slice(&2i32.to_le_bytes()); // Version 2, single-part scanline
+
+    // Channels must be listed in alphabetical order.
+    let mut channels: Vec<u8> = Vec::new();
+    for name in ["B", "G", "R"] {
+        channels.extend_from_slice(name.as_bytes());
+        channels.push(0);
+        channels.extend_from_slice(&pixel_type.to_le_bytes());
+        channels.extend_from_slice(&[0, 0, 0, 0]); // pLinear and reserved bytes
+        channels.extend_from_slice(&1i32.to_le_bytes()); // x sampling
+        channels.extend_from_slice(&1i32.to_le_bytes()); // y sampling
+    }
+    channels.push(0);
+    let window: Vec<u8> = [0, 0, x_max, y_max]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+
+    write_attribute(&mut header, "channels", "chlist", &channels);
+    write_attribute(&mut header, "compression", "compression", &[0]); // NO_COMPRESSION
+    write_attribute(&mut header, "dataWindow", "box2i", &window);
+    write_attribute(&mut header, "displayWindow", "box2i", &window);
+    write_attribute(&mut header, "lineOrder", "lineOrder", &[0]); // INCREASING_Y
+    write_attribute(
+        &mut header,
+        "pixelAspectRatio",
+        "float",
+        &1f32.to_le_bytes(),
+    );
+    write_attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+    write_attribute(
+        &mut header,
+        "screenWindowWidth",
+        "float",
+        &1f32.to_le_bytes(),
+    );
+    header.push(0); // End of header
+
+    // Without compression every scanline is its own block, so the offsets are evenly spaced.
+    let line_size: usize = width * 3 * sample_size;
+    let block_size: usize = 8 + line_size;
+    let first_block: usize = header.len() + 8 * height;
+    for y in 0..height {
+        header.extend_from_slice(&((first_block + y * block_size) as u64).to_le_bytes());
+    }
+    out.write_all(&header)?;
+
+    let mut block: Vec<u8> = Vec::with_capacity(block_size);
+    for (y, row) in image.rows().enumerate() {
+        block.clear();
+        block.extend_from_slice(&(y as i32).to_le_bytes());
+        block.extend_from_slice(&(line_size as i32).to_le_bytes());
+        for channel in [2, 1, 0] {
+            for pixel in row {
+                match pixel_type {
+                    PIXEL_TYPE_FLOAT => block.extend_from_slice(&pixel[channel].to_le_bytes()),
+                    _ => block.extend_from_slice(&f16::from_f32(pixel[channel]).to_le_bytes()),
+                }
+            }
+        }
+        out.write_all(&block)?;
+    }
+    Ok(())
+}
+
+fn write_attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
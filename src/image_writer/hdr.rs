@@ -0,0 +1,105 @@
+use std::io::{self, Write};
+
+use crate::framebuffer::Framebuffer;
+
+pub fn write_pfm(out: &mut impl Write, image: &Framebuffer) -> io::Result<()> {
+    // A negative scale marks little endian samples. PFM stores the bottom row first.
+    write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+    for row in image.rows().rev() {
+        let bytes: Vec<u8> = row.iter().flatten().flat_map(|c| c.to_le_bytes()).collect();
+        out.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+pub fn write_rgbe(out: &mut impl Write, image: &Framebuffer) -> io::Result<()> {
+    write!(
+        out,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        image.height(),
+        image.width()
+    )?;
+
+    let width: usize = image.width();
+    for row in image.rows() {
+        let pixels: Vec<[u8; 4]> = row.iter().map(|p| float_to_rgbe(*p)).collect();
+
+        // Run length encoding is only defined for scanlines between 8 and 32767 pixels wide.
+        if !(8..=0x7fff).contains(&width) {
+            out.write_all(&pixels.concat())?;
+            continue;
+        }
+        out.write_all(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8])?;
+        for component in 0..4 {
+            let data: Vec<u8> = pixels.iter().map(|p| p[component]).collect();
+            write_rle_component(out, &data)?;
+        }
+    }
+    Ok(())
+}
+
+fn float_to_rgbe(pixel: [f32; 3]) -> [u8; 4] {
+    // Shared exponent encoding: the mantissas are scaled by the exponent of the brightest channel.
+    let [r, g, b] = pixel.map(|c| match c.is_nan() {
+        true => 0.0,
+        false => c.max(0.0),
+    });
+    let v: f32 = r.max(g).max(b);
+    if v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    // frexp: v = m * 2^e with m in [0.5, 1). Values this large are always normal floats.
+    let e: i32 = ((v.to_bits() >> 23) & 0xff) as i32 - 126;
+    let scale: f32 = 256.0 / 2f32.powi(e);
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (e + 128) as u8,
+    ]
+}
+
+fn write_rle_component(out: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    // Adaptive run length encoding of one component of a scanline, after Greg Ward's rgbe.c:
+    // runs of at least MIN_RUN equal bytes are written as (128 + count, value) and everything in
+    // between as literal (count, bytes...) blocks.
+    const MIN_RUN: usize = 4;
+    let n: usize = data.len();
+    let mut cur: usize = 0;
+
+    while cur < n {
+        let mut beg_run: usize = cur;
+        let mut run_count: usize = 0;
+        let mut old_run_count: usize = 0;
+        while run_count < MIN_RUN && beg_run < n {
+            beg_run += run_count;
+            old_run_count = run_count;
+            run_count = 1;
+            while beg_run + run_count < n
+                && run_count < 127
+                && data[beg_run] == data[beg_run + run_count]
+            {
+                run_count += 1;
+            }
+        }
+
+        // A short run directly before the next long one is still worth encoding as a run.
+        if old_run_count > 1 && old_run_count == beg_run - cur {
+            out.write_all(&[128 + old_run_count as u8, data[cur]])?;
+            cur = beg_run;
+        }
+
+        while cur < beg_run {
+            let nonrun_count: usize = (beg_run - cur).min(128);
+            out.write_all(&[nonrun_count as u8])?;
+            out.write_all(&data[cur..cur + nonrun_count])?;
+            cur += nonrun_count;
+        }
+
+        if run_count >= MIN_RUN {
+            out.write_all(&[128 + run_count as u8, data[beg_run]])?;
+            cur += run_count;
+        }
+    }
+    Ok(())
+}
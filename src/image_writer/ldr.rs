@@ -0,0 +1,69 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use crate::image::Image;
+
+// Binary (P6) portable pixmap. Samples above 255 take two bytes, most significant first.
+pub fn write_ppm_binary(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    let header: String = format!(
+        "P6\n{} {}\n{}\n",
+        image.width(),
+        image.height(),
+        image.max_value()
+    );
+    let data: Vec<u8> = match image.max_value() {
+        0..=255 => image.samples().iter().map(|&s| s as u8).collect(),
+        _ => image
+            .samples()
+            .iter()
+            .flat_map(|s| s.to_be_bytes())
+            .collect(),
+    };
+    out.write_all(header.as_bytes())?;
+    out.write_all(&data)
+}
+
+// ASCII (P3) portable pixmap, one pixel per line.
+pub fn write_ppm_ascii(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    let mut text: String = format!(
+        "P3\n{} {}\n{}\n",
+        image.width(),
+        image.height(),
+        image.max_value()
+    );
+    for pixel in image.samples().chunks_exact(3) {
+        // Formatting into a String cannot fail.
+        let _ = writeln!(text, "{} {} {}", pixel[0], pixel[1], pixel[2]);
+    }
+    out.write_all(text.as_bytes())
+}
+
+pub fn write_png(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(out, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    // Pixels are sRGB encoded; the sRGB chunk says so, along with matching gAMA and cHRM chunks
+    // for decoders that do not understand it.
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+
+    let data: Vec<u8> = match image.max_value() {
+        0..=255 => {
+            encoder.set_depth(png::BitDepth::Eight);
+            image.samples().iter().map(|&s| s as u8).collect()
+        }
+        _ => {
+            // PNG stores 16-bit samples big endian.
+            encoder.set_depth(png::BitDepth::Sixteen);
+            image
+                .samples()
+                .iter()
+                .flat_map(|s| s.to_be_bytes())
+                .collect()
+        }
+    };
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&data).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
@@ -0,0 +1,143 @@
+mod exr;
+mod hdr;
+mod ldr;
+
+use std::{io, io::Write, path::Path};
+
+use crate::{framebuffer::Framebuffer, image::Image, tonemap::ToneMapping};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    PpmAscii,
+    Png,
+    Pfm,
+    Hdr,
+    Exr,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension: String = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Some(Self::Ppm),
+            "png" => Some(Self::Png),
+            "pfm" => Some(Self::Pfm),
+            "hdr" => Some(Self::Hdr),
+            "exr" => Some(Self::Exr),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ppm => "PPM",
+            Self::PpmAscii => "ASCII PPM",
+            Self::Png => "PNG",
+            Self::Pfm => "PFM",
+            Self::Hdr => "Radiance HDR",
+            Self::Exr => "OpenEXR",
+        }
+    }
+
+    pub fn default_bit_depth(self) -> BitDepth {
+        match self {
+            Self::Ppm | Self::PpmAscii | Self::Png => BitDepth::Eight,
+            Self::Exr => BitDepth::Sixteen,
+            Self::Pfm | Self::Hdr => BitDepth::ThirtyTwo,
+        }
+    }
+
+    pub fn supports(self, bit_depth: BitDepth) -> bool {
+        match self {
+            Self::Ppm | Self::PpmAscii | Self::Png => bit_depth != BitDepth::ThirtyTwo,
+            Self::Exr => bit_depth != BitDepth::Eight,
+            // RGBE has a fixed shared-exponent encoding; 32 stands for its float range.
+            Self::Pfm | Self::Hdr => bit_depth == BitDepth::ThirtyTwo,
+        }
+    }
+
+    pub fn is_high_dynamic_range(self) -> bool {
+        matches!(self, Self::Pfm | Self::Hdr | Self::Exr)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+    ThirtyTwo,
+}
+
+impl BitDepth {
+    pub fn bits(self) -> u8 {
+        match self {
+            Self::Eight => 8,
+            Self::Sixteen => 16,
+            Self::ThirtyTwo => 32,
+        }
+    }
+
+    // Largest integer sample value, for the integer image formats.
+    pub fn max_value(self) -> u16 {
+        match self {
+            Self::Eight => u8::MAX as u16,
+            _ => u16::MAX,
+        }
+    }
+}
+
+// Encodes a rendered image in the chosen format and bit depth. Low dynamic range formats are
+// tone mapped and sRGB encoded; high dynamic range formats store the linear radiance as is.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageWriter {
+    format: ImageFormat,
+    bit_depth: BitDepth,
+    tone_mapping: ToneMapping,
+}
+
+impl ImageWriter {
+    pub fn new(format: ImageFormat, bit_depth: Option<BitDepth>) -> Result<Self, String> {
+        let bit_depth: BitDepth = bit_depth.unwrap_or(format.default_bit_depth());
+        match format.supports(bit_depth) {
+            true => Ok(Self {
+                format,
+                bit_depth,
+                tone_mapping: ToneMapping::default(),
+            }),
+            false => Err(format!(
+                "{} output does not support {}-bit channels",
+                format.name(),
+                bit_depth.bits()
+            )),
+        }
+    }
+
+    // Only used by the low dynamic range formats.
+    pub fn with_tone_mapping(mut self, tone_mapping: ToneMapping) -> Self {
+        self.tone_mapping = tone_mapping;
+        self
+    }
+
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    pub fn tone_mapping(&self) -> &ToneMapping {
+        &self.tone_mapping
+    }
+
+    pub fn write(&self, out: &mut impl Write, image: &Framebuffer) -> io::Result<()> {
+        let ldr_image =
+            || Image::from_framebuffer(image, &self.tone_mapping, self.bit_depth.max_value());
+        match self.format {
+            ImageFormat::Ppm => ldr::write_ppm_binary(out, &ldr_image()),
+            ImageFormat::PpmAscii => ldr::write_ppm_ascii(out, &ldr_image()),
+            ImageFormat::Png => ldr::write_png(out, &ldr_image()),
+            ImageFormat::Pfm => hdr::write_pfm(out, image),
+            ImageFormat::Hdr => hdr::write_rgbe(out, image),
+            ImageFormat::Exr => exr::write_exr(out, image, self.bit_depth),
+        }
+    }
+}
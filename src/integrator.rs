@@ -0,0 +1,164 @@
+use crate::{
+    background::Background,
+    color::Color,
+    hittables::hittable::{HitRecord, HittableType},
+    interval::Interval,
+    lights::LightList,
+    materials::material::{Material, Scatter},
+    ray::Ray,
+    sampler::Sampler,
+};
+
+// Path tracer with next-event estimation. At every surface that scatters light over a spread of
+// directions, light is gathered twice: by sampling a point on a light (an emissive shape, or the
+// environment map or sun of the background) and casting a shadow ray towards it, and by following
+// the scattered ray. Each is weighted against the density with which the other would have found
+// the same light (multiple importance sampling with the power heuristic), so small or distant
+// lights converge quickly without broad ones being counted twice.
+pub struct Integrator<'a> {
+    world: &'a HittableType,
+    background: &'a Background,
+    lights: LightList,
+}
+
+impl<'a> Integrator<'a> {
+    pub fn new(world: &'a HittableType, background: &'a Background) -> Self {
+        Self {
+            world,
+            background,
+            lights: LightList::new(world),
+        }
+    }
+
+    // Radiance arriving at the origin of `r`, following at most `depth` bounces.
+    pub fn radiance(&self, r: &Ray, depth: i16, sampler: &mut Sampler) -> Color {
+        self.trace(r, depth, sampler, None)
+    }
+
+    // `scatter_pdf` is set when the ray was scattered off a surface that also sampled the lights
+    // or the background directly, to the density with which the surface picked the ray.
+    fn trace(&self, r: &Ray, depth: i16, sampler: &mut Sampler, scatter_pdf: Option<f64>) -> Color {
+        // If we've exceeded the ray bounce limit, no more light is gathered
+        if depth <= 0 {
+            return Color::default();
+        }
+
+        let mut rec: HitRecord = HitRecord::default();
+        if !self
+            .world
+            .hit(r, &Interval::new(0.001, f64::INFINITY), &mut rec)
+        {
+            let light: Color = self.background.color(r.direction());
+            return match scatter_pdf {
+                Some(pdf) => power_heuristic(pdf, self.background.pdf(r.direction())) * light,
+                None => light,
+            };
+        }
+
+        let emitted: Color = match scatter_pdf {
+            Some(pdf) if !self.lights.is_empty() && !rec.mat.emitted().near_zero() => {
+                power_heuristic(pdf, self.lights.pdf(r, &rec)) * rec.mat.emitted()
+            }
+            _ => rec.mat.emitted(),
+        };
+        let mut scattered: Ray = Ray::default();
+        let mut attenuation: Color = Color::default();
+        let is_scattered: bool = match &*rec.mat {
+            Material::Metal(m) => m.scatter(r, &rec, &mut attenuation, &mut scattered, sampler),
+            Material::Lambertian(l) => {
+                l.scatter(r, &rec, &mut attenuation, &mut scattered, sampler)
+            }
+            Material::Dielectric(d) => {
+                d.scatter(r, &rec, &mut attenuation, &mut scattered, sampler)
+            }
+            Material::DiffuseLight(l) => {
+                l.scatter(r, &rec, &mut attenuation, &mut scattered, sampler)
+            }
+        };
+        // Diffuse and glossy surfaces sample the lights directly as well; specular ones can't.
+        // Glossy ones do so even when the scattered ray is absorbed below the surface.
+        let mut direct: Color = Color::default();
+        let mut next_pdf: Option<f64> = None;
+        if let Some((_, pdf)) = rec.mat.scattering(r, &rec, scattered.direction()) {
+            if self.background.is_sampled() {
+                direct += self.sample_background(r, &rec, sampler);
+            }
+            if !self.lights.is_empty() {
+                direct += self.sample_light(r, &rec, sampler);
+            }
+            if self.background.is_sampled() || !self.lights.is_empty() {
+                next_pdf = Some(pdf);
+            }
+        }
+        if !is_scattered {
+            return emitted + direct;
+        }
+        emitted + direct + attenuation * self.trace(&scattered, depth - 1, sampler, next_pdf)
+    }
+
+    // Light reaching a surface from one direction picked from the background, weighted against
+    // the surface's own scattering.
+    fn sample_background(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut Sampler) -> Color {
+        let Some((direction, light_pdf)) = self.background.sample(sampler.get_2d()) else {
+            return Color::default();
+        };
+        let Some((bsdf, scatter_pdf)) = rec.mat.scattering(r_in, rec, direction) else {
+            return Color::default();
+        };
+        if scatter_pdf <= 0.0 {
+            return Color::default();
+        }
+        let shadow: Ray = Ray::new(rec.p, direction);
+        if self.world.hit(
+            &shadow,
+            &Interval::new(0.001, f64::INFINITY),
+            &mut HitRecord::default(),
+        ) {
+            return Color::default();
+        }
+        (power_heuristic(light_pdf, scatter_pdf) / light_pdf)
+            * bsdf
+            * self.background.color(direction)
+    }
+
+    // Light reaching a surface from a point picked on one of the emissive shapes, weighted
+    // against the surface's own scattering.
+    fn sample_light(&self, r_in: &Ray, rec: &HitRecord, sampler: &mut Sampler) -> Color {
+        let u: f64 = sampler.get_1d();
+        let Some((direction, light_pdf, light)) = self.lights.sample(rec.p, u, sampler.get_2d())
+        else {
+            return Color::default();
+        };
+        let Some((bsdf, scatter_pdf)) = rec.mat.scattering(r_in, rec, direction) else {
+            return Color::default();
+        };
+        if scatter_pdf <= 0.0 {
+            return Color::default();
+        }
+        // Find the point on the light, then check nothing lies in between.
+        let shadow: Ray = Ray::new(rec.p, direction);
+        let mut light_rec: HitRecord = HitRecord::default();
+        if !light.hit(
+            &shadow,
+            &Interval::new(0.001, f64::INFINITY),
+            &mut light_rec,
+        ) || self.world.hit(
+            &shadow,
+            &Interval::new(0.001, light_rec.t * (1.0 - 1e-9)),
+            &mut HitRecord::default(),
+        ) {
+            return Color::default();
+        }
+        (power_heuristic(light_pdf, scatter_pdf) / light_pdf) * bsdf * light_rec.mat.emitted()
+    }
+}
+
+// Multiple importance sampling weight of a sample drawn with density `f` when a second strategy
+// would have drawn it with density `g`.
+fn power_heuristic(f: f64, g: f64) -> f64 {
+    let (f2, g2) = (f * f, g * g);
+    match f2 + g2 > 0.0 {
+        true => f2 / (f2 + g2),
+        false => 0.0,
+    }
+}
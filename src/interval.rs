@@ -1,4 +1,4 @@
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Interval {
     pub min: f64,
     pub max: f64,
@@ -18,6 +18,14 @@ impl Interval {
         Self { min, max }
     }
 
+    pub fn surrounding(a: Interval, b: Interval) -> Self {
+        // Create the interval tightly enclosing the two input intervals.
+        Self {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
     pub fn size(self) -> f64 {
         self.max - self.min
     }
@@ -38,6 +46,11 @@ impl Interval {
         }
     }
 
+    pub fn expand(self, delta: f64) -> Interval {
+        let padding: f64 = delta / 2.0;
+        Interval::new(self.min - padding, self.max + padding)
+    }
+
     pub const EMPTY: Interval = Interval::new(f64::INFINITY, -f64::INFINITY);
     pub const UNIVERSE: Interval = Interval::new(-f64::INFINITY, f64::INFINITY);
 }
@@ -0,0 +1,75 @@
+//! A CPU path tracer following Peter Shirley's "Ray Tracing in One Weekend", grown with meshes,
+//! a BVH, TOML scene files and high dynamic range output.
+//!
+//! Build a `Scene` either by loading a scene file with `Scene::load`, from `builtin_scene`, or by
+//! adding hittables to a `HittableList` and pairing it with a `CameraConfig`. `Scene::render`
+//! returns a linear `Framebuffer`, which an `ImageWriter` encodes as PPM, PNG, PFM, Radiance HDR
+//! or OpenEXR.
+
+pub mod aabb;
+pub mod accumulator;
+pub mod background;
+pub mod builtin_scenes;
+pub mod camera;
+pub mod checkpoint;
+pub mod color;
+pub mod distribution;
+pub mod filter;
+pub mod framebuffer;
+pub mod hittables;
+pub mod image;
+pub mod image_writer;
+pub mod integrator;
+pub mod interval;
+pub mod lights;
+pub mod loaders;
+pub mod materials;
+pub mod progress;
+pub mod ray;
+pub mod rng;
+pub mod sampler;
+pub mod scene;
+pub mod sky;
+pub mod tile;
+pub mod tonemap;
+pub mod util;
+pub mod vec3;
+
+pub use accumulator::{Accumulator, PixelSamples, TileSamples};
+pub use background::{Background, EnvironmentMap};
+pub use builtin_scenes::{builtin_scene, BUILTIN_SCENES};
+pub use camera::{AdaptiveSampling, Camera, CameraConfig, RenderError, RenderOutput};
+pub use checkpoint::Checkpoint;
+pub use color::Color;
+pub use filter::{Filter, FilterKind};
+pub use framebuffer::Framebuffer;
+pub use hittables::{
+    bvh::BvhNode,
+    hittable::{HitRecord, Hittable, HittableType},
+    hittable_list::HittableList,
+    mesh::TriangleMesh,
+    sphere::Sphere,
+    triangle::Triangle,
+};
+pub use image_writer::{BitDepth, ImageFormat, ImageWriter};
+pub use integrator::Integrator;
+pub use lights::{Light, LightList};
+pub use loaders::{
+    hdr::load_hdr,
+    obj::{load_obj, load_obj_with_libraries},
+    ply::load_ply,
+    LoadError,
+};
+pub use materials::{
+    dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
+    material::Material, metal::Metal,
+};
+pub use progress::{CancellationToken, NoProgress, Progress, ProgressObserver};
+pub use ray::{Point3, Ray};
+pub use rng::Rng;
+pub use sampler::{Sampler, SamplerKind};
+pub use scene::{Scene, SceneError};
+pub use sky::Sky;
+pub use tile::{TileConfig, TileOrder};
+pub use tonemap::{ToneMapper, ToneMapping};
+pub use vec3::Vec3;
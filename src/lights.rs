@@ -0,0 +1,160 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    color::luminance,
+    distribution::Distribution1D,
+    hittables::{
+        bvh::BvhNode,
+        hittable::{HitRecord, Hittable, HittableType},
+        sphere::Sphere,
+        triangle::Triangle,
+    },
+    interval::Interval,
+    materials::material::Material,
+    ray::{Point3, Ray},
+    vec3::Vec3,
+};
+
+// An emissive shape that surfaces sample directly.
+#[derive(Clone)]
+pub enum Light {
+    Sphere(Arc<Sphere>),
+    Triangle(Arc<Triangle>),
+}
+
+impl Light {
+    pub fn material(&self) -> &Material {
+        match self {
+            Light::Sphere(s) => s.material(),
+            Light::Triangle(t) => t.material(),
+        }
+    }
+
+    pub fn area(&self) -> f64 {
+        match self {
+            Light::Sphere(s) => s.area(),
+            Light::Triangle(t) => t.area(),
+        }
+    }
+
+    // Address of the shape, matching `HitRecord::shape` for hits on it.
+    pub fn shape(&self) -> usize {
+        match self {
+            Light::Sphere(s) => Arc::as_ptr(s) as usize,
+            Light::Triangle(t) => Arc::as_ptr(t) as usize,
+        }
+    }
+
+    pub fn hit(&self, r: &Ray, ray_t: &Interval, rec: &mut HitRecord) -> bool {
+        match self {
+            Light::Sphere(s) => s.hit(r, ray_t, rec),
+            Light::Triangle(t) => t.hit(r, ray_t, rec),
+        }
+    }
+
+    // Picks a direction from `origin` towards the light, returning it with its density per unit
+    // solid angle.
+    pub fn sample_direction(&self, origin: Point3, u: (f64, f64)) -> Option<(Vec3, f64)> {
+        match self {
+            Light::Sphere(s) => s.sample_direction(origin, u),
+            Light::Triangle(t) => t.sample_direction(origin, u),
+        }
+    }
+
+    // Density per unit solid angle with which `sample_direction` picks the direction from
+    // `origin` that hits the light at `rec`.
+    pub fn direction_pdf(&self, origin: Point3, rec: &HitRecord) -> f64 {
+        match self {
+            Light::Sphere(s) => s.direction_pdf(origin),
+            Light::Triangle(t) => t.direction_pdf(origin, rec.p),
+        }
+    }
+}
+
+// Every emissive sphere and triangle of a world, meshes included, each picked for sampling in
+// proportion to the power it emits.
+pub struct LightList {
+    lights: Vec<Light>,
+    distribution: Distribution1D,
+    // Index into `lights` by the shape's address, to find the light a ray hit.
+    indices: HashMap<usize, usize>,
+}
+
+impl LightList {
+    pub fn new(world: &HittableType) -> Self {
+        let mut lights: Vec<Light> = Vec::new();
+        collect(world, &mut lights);
+        let power: Vec<f64> = lights
+            .iter()
+            .map(|light| luminance(light.material().emitted()) * light.area())
+            .collect();
+        Self {
+            distribution: Distribution1D::new(power),
+            indices: lights
+                .iter()
+                .enumerate()
+                .map(|(index, light)| (light.shape(), index))
+                .collect(),
+            lights,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    // Picks a light with `u` and a direction from `origin` towards it with `v`, returning the
+    // direction, its density per unit solid angle over all lights, and the light.
+    pub fn sample(&self, origin: Point3, u: f64, v: (f64, f64)) -> Option<(Vec3, f64, &Light)> {
+        if self.lights.is_empty() {
+            return None;
+        }
+        let (_, _, index) = self.distribution.sample(u);
+        let light: &Light = &self.lights[index];
+        let (direction, pdf) = light.sample_direction(origin, v)?;
+        Some((direction, self.probability(index) * pdf, light))
+    }
+
+    // Density per unit solid angle with which `sample` picks the direction of `r`, given that
+    // the first thing it hits is `rec`. Only the shape hit can have been sampled in that
+    // direction and found unoccluded.
+    pub fn pdf(&self, r: &Ray, rec: &HitRecord) -> f64 {
+        match self.indices.get(&rec.shape) {
+            Some(&index) => {
+                self.probability(index) * self.lights[index].direction_pdf(r.origin(), rec)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn probability(&self, index: usize) -> f64 {
+        self.distribution.pdf(index) / self.lights.len() as f64
+    }
+}
+
+fn collect(object: &HittableType, lights: &mut Vec<Light>) {
+    let emits = |material: &Material| luminance(material.emitted()) > 0.0;
+    match object {
+        HittableType::Sphere(s) => {
+            if emits(s.material()) {
+                lights.push(Light::Sphere(s.clone()));
+            }
+        }
+        HittableType::Triangle(t) => {
+            if emits(t.material()) {
+                lights.push(Light::Triangle(t.clone()));
+            }
+        }
+        HittableType::List(l) => l.objects.iter().for_each(|o| collect(o, lights)),
+        HittableType::Bvh(b) => collect_bvh(b, lights),
+        HittableType::Mesh(m) => collect_bvh(m.bvh(), lights),
+    }
+}
+
+fn collect_bvh(node: &BvhNode, lights: &mut Vec<Light>) {
+    let (left, right) = node.children();
+    collect(left, lights);
+    if !Arc::ptr_eq(left, right) {
+        collect(right, lights);
+    }
+}
@@ -0,0 +1,289 @@
+use std::path::Path;
+
+use super::LoadError;
+use crate::{color::Color, framebuffer::Framebuffer};
+
+// Loads a high dynamic range image, Radiance RGBE (.hdr, .pic) or portable float map (.pfm), into
+// a linear framebuffer. Errors in the pixel data point at the line with the image size.
+pub fn load_hdr(path: impl AsRef<Path>) -> Result<Framebuffer, LoadError> {
+    let path: &Path = path.as_ref();
+    let data: Vec<u8> = std::fs::read(path).map_err(|e| LoadError::io(path, e))?;
+    let result = match data.starts_with(b"PF") || data.starts_with(b"Pf") {
+        true => read_pfm(&data),
+        false => read_rgbe(&data),
+    };
+    result.map_err(|(line, msg)| LoadError::parse(path, line, msg))
+}
+
+// Splits the next newline-terminated header line off `data`.
+fn next_line<'a>(data: &mut &'a [u8], line_no: &mut usize) -> Result<&'a str, (usize, String)> {
+    *line_no += 1;
+    let end: usize = data
+        .iter()
+        .position(|&b| b == b'\n')
+        .ok_or_else(|| (*line_no, "unexpected end of header".to_string()))?;
+    let line: &[u8] = &data[..end];
+    *data = &data[end + 1..];
+    std::str::from_utf8(line)
+        .map(|l| l.trim_end_matches('\r'))
+        .map_err(|_| (*line_no, "header is not text".to_string()))
+}
+
+fn read_rgbe(mut data: &[u8]) -> Result<Framebuffer, (usize, String)> {
+    let mut line_no: usize = 0;
+    if !next_line(&mut data, &mut line_no)?.starts_with("#?") {
+        return Err((1, "not a Radiance HDR file".to_string()));
+    }
+    loop {
+        let line: &str = next_line(&mut data, &mut line_no)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err((line_no, format!("unsupported pixel format '{}'", format)));
+            }
+        }
+    }
+
+    // Only the standard orientation, top row first and left to right, is supported.
+    let resolution: &str = next_line(&mut data, &mut line_no)?;
+    let size_line: usize = line_no;
+    let (height, width) = match resolution.split_whitespace().collect::<Vec<&str>>()[..] {
+        ["-Y", h, "+X", w] => match (h.parse::<usize>(), w.parse::<usize>()) {
+            (Ok(h), Ok(w)) if h > 0 && w > 0 => (h, w),
+            _ => return Err((line_no, format!("invalid image size '{}'", resolution))),
+        },
+        _ => {
+            return Err((
+                line_no,
+                format!("unsupported image orientation '{}'", resolution),
+            ))
+        }
+    };
+
+    // Run length encoding packs at most 127 pixels into every 8 bytes; check before allocating.
+    if width.saturating_mul(height) / 16 > data.len() {
+        return Err((size_line, "truncated pixel data".to_string()));
+    }
+    let mut image: Framebuffer = Framebuffer::new(width, height);
+    let mut scanline: Vec<[u8; 4]> = vec![[0; 4]; width];
+    for y in 0..height {
+        let err = |message: &str| (size_line, format!("scanline {}: {}", y, message));
+        let run_length: bool = (8..=0x7fff).contains(&width)
+            && data.len() >= 4
+            && data[..2] == [2, 2]
+            && ((data[2] as usize) << 8 | data[3] as usize) == width;
+        match run_length {
+            // Each of the four components is run length encoded separately.
+            true => {
+                data = &data[4..];
+                for component in 0..4 {
+                    let mut x: usize = 0;
+                    while x < width {
+                        let (&count, rest) = data.split_first().ok_or_else(|| err("truncated"))?;
+                        data = rest;
+                        match count > 128 {
+                            true => {
+                                let count: usize = count as usize - 128;
+                                let (&value, rest) =
+                                    data.split_first().ok_or_else(|| err("truncated"))?;
+                                data = rest;
+                                if x + count > width {
+                                    return Err(err("run overflows the scanline"));
+                                }
+                                scanline[x..x + count]
+                                    .iter_mut()
+                                    .for_each(|p| p[component] = value);
+                                x += count;
+                            }
+                            false => {
+                                let count: usize = count as usize;
+                                if count == 0 || x + count > width {
+                                    return Err(err("invalid run"));
+                                }
+                                if data.len() < count {
+                                    return Err(err("truncated"));
+                                }
+                                for (p, &value) in scanline[x..x + count].iter_mut().zip(data) {
+                                    p[component] = value;
+                                }
+                                data = &data[count..];
+                                x += count;
+                            }
+                        }
+                    }
+                }
+            }
+            false => {
+                if data.len() < width * 4 {
+                    return Err(err("truncated"));
+                }
+                for (p, bytes) in scanline.iter_mut().zip(data.chunks_exact(4)) {
+                    p.copy_from_slice(bytes);
+                }
+                data = &data[width * 4..];
+            }
+        }
+        for (x, &rgbe) in scanline.iter().enumerate() {
+            let [r, g, b] = rgbe_to_float(rgbe);
+            image.set(x, y, Color::new(r, g, b));
+        }
+    }
+    Ok(image)
+}
+
+fn rgbe_to_float([r, g, b, e]: [u8; 4]) -> [f64; 3] {
+    // The mantissas share the exponent e, biased by 128, of a binary point before their 8 bits.
+    // Reading each mantissa as the middle of its quantisation step keeps dim values unbiased.
+    match e {
+        0 => [0.0; 3],
+        e => {
+            let scale: f64 = 2f64.powi(e as i32 - 136);
+            [r, g, b].map(|m| (m as f64 + 0.5) * scale)
+        }
+    }
+}
+
+fn read_pfm(mut data: &[u8]) -> Result<Framebuffer, (usize, String)> {
+    // A header of four whitespace separated tokens, the last followed by a single whitespace byte,
+    // then rows of f32 samples from the bottom row up.
+    let mut tokens: Vec<String> = Vec::new();
+    while tokens.len() < 4 {
+        let truncated = || (1, "unexpected end of header".to_string());
+        let start: usize = data
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .ok_or_else(truncated)?;
+        let end: usize = start
+            + data[start..]
+                .iter()
+                .position(|b| b.is_ascii_whitespace())
+                .ok_or_else(truncated)?;
+        tokens.push(String::from_utf8_lossy(&data[start..end]).into_owned());
+        data = &data[end + 1..];
+    }
+    let channels: usize = match tokens[0].as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err((1, "not a portable float map".to_string())),
+    };
+    let (width, height) = match (tokens[1].parse::<usize>(), tokens[2].parse::<usize>()) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+        _ => {
+            let size: String = tokens[1..3].join(" ");
+            return Err((1, format!("invalid image size '{}'", size)));
+        }
+    };
+    // The sign of the scale gives the byte order.
+    let scale: f64 = tokens[3]
+        .parse()
+        .ok()
+        .filter(|s: &f64| *s != 0.0)
+        .ok_or_else(|| (1, format!("invalid scale '{}'", tokens[3])))?;
+
+    // The size comes from the file, so check the pixel data is all there before allocating.
+    let row_bytes: usize = width
+        .checked_mul(channels * 4)
+        .ok_or_else(|| (1, "image too large".to_string()))?;
+    if row_bytes
+        .checked_mul(height)
+        .is_none_or(|bytes| data.len() < bytes)
+    {
+        return Err((1, "truncated pixel data".to_string()));
+    }
+    let mut image: Framebuffer = Framebuffer::new(width, height);
+    for (row, bytes) in data.chunks_exact(row_bytes).take(height).enumerate() {
+        let samples: Vec<f64> = bytes
+            .chunks_exact(4)
+            .map(|b| {
+                let b: [u8; 4] = [b[0], b[1], b[2], b[3]];
+                match scale < 0.0 {
+                    true => f32::from_le_bytes(b),
+                    false => f32::from_be_bytes(b),
+                }
+            })
+            .map(|s| s as f64)
+            .collect();
+        for (x, pixel) in samples.chunks_exact(channels).enumerate() {
+            let color = match pixel {
+                [r, g, b] => Color::new(*r, *g, *b),
+                [l, ..] => Color::new(*l, *l, *l),
+                [] => Color::default(),
+            };
+            image.set(x, height - 1 - row, color);
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image_writer::{ImageFormat, ImageWriter};
+
+    // A ramp with dark, bright and constant stretches, so run length encoding has both runs and
+    // literal blocks to write.
+    fn test_image(width: usize, height: usize) -> Framebuffer {
+        let mut image: Framebuffer = Framebuffer::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let color: Color = match x % 12 < 5 {
+                    true => Color::new(0.25, 0.5, 0.75),
+                    false => Color::new(x as f64 * 3.7, y as f64 * 0.01, (x * y) as f64 / 7.0),
+                };
+                image.set(x, y, color);
+            }
+        }
+        image
+    }
+
+    fn encode(format: ImageFormat, image: &Framebuffer) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+        ImageWriter::new(format, None)
+            .unwrap()
+            .write(&mut data, image)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn pfm_round_trip_is_exact() {
+        for (width, height) in [(1, 1), (5, 3), (40, 7)] {
+            let image: Framebuffer = test_image(width, height);
+            let read: Framebuffer = read_pfm(&encode(ImageFormat::Pfm, &image)).unwrap();
+            assert_eq!((read.width(), read.height()), (width, height));
+            assert!(read.pixels() == image.pixels());
+        }
+    }
+
+    #[test]
+    fn rgbe_round_trip_within_shared_exponent_precision() {
+        // Narrower than 8 pixels is written flat, wider run length encoded.
+        for (width, height) in [(1, 1), (5, 3), (40, 7)] {
+            let image: Framebuffer = test_image(width, height);
+            let read: Framebuffer = read_rgbe(&encode(ImageFormat::Hdr, &image)).unwrap();
+            assert_eq!((read.width(), read.height()), (width, height));
+            for (expected, found) in image.pixels().iter().zip(read.pixels()) {
+                // The mantissas have 8 bits relative to the brightest channel.
+                let tolerance: f32 = expected.iter().cloned().fold(0.0, f32::max) / 128.0;
+                for c in 0..3 {
+                    assert!(
+                        (expected[c] - found[c]).abs() <= tolerance,
+                        "{:?} read back as {:?}",
+                        expected,
+                        found
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pfm_size_beyond_the_data_is_an_error() {
+        let mut data: Vec<u8> = encode(ImageFormat::Pfm, &test_image(4, 4));
+        data.truncate(data.len() - 1);
+        assert!(read_pfm(&data).is_err());
+        assert!(read_pfm(b"PF\n4611686018427387904 1\n-1\nabcd").is_err());
+    }
+}
@@ -0,0 +1,365 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use super::LoadError;
+use crate::{
+    color::Color,
+    hittables::mesh::{MeshBuffers, MeshFace, MeshVertex, TriangleMesh},
+    materials::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
+        material::Material, metal::Metal,
+    },
+    ray::Point3,
+    vec3::Vec3,
+};
+
+// Loads a Wavefront OBJ file into a triangle mesh. Polygons are fan-triangulated, and materials
+// referenced through `mtllib`/`usemtl` are mapped onto the closest built-in material. Faces
+// without a `usemtl` use `default_material`.
+pub fn load_obj(
+    path: impl AsRef<Path>,
+    default_material: Material,
+) -> Result<TriangleMesh, LoadError> {
+    load_obj_with_libraries(path, default_material).map(|(mesh, _)| mesh)
+}
+
+// Like `load_obj`, also returning the paths of the material libraries the file pulled in.
+pub fn load_obj_with_libraries(
+    path: impl AsRef<Path>,
+    default_material: Material,
+) -> Result<(TriangleMesh, Vec<PathBuf>), LoadError> {
+    let path: &Path = path.as_ref();
+    let source: String = std::fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+
+    let mut buffers: MeshBuffers = MeshBuffers::default();
+    let mut faces: Vec<MeshFace> = Vec::new();
+    let mut materials: HashMap<String, Arc<Material>> = HashMap::new();
+    let mut current_mat: Arc<Material> = Arc::new(default_material);
+    let mut libraries: Vec<PathBuf> = Vec::new();
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no: usize = idx + 1;
+        let err = |message: String| LoadError::parse(path, line_no, message);
+        let mut tokens = strip_comment(raw_line).split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                let [x, y, z] = parse_floats::<3>(&args).map_err(err)?;
+                buffers.positions.push(Point3::new(x, y, z));
+            }
+            "vn" => {
+                let [x, y, z] = parse_floats::<3>(&args).map_err(err)?;
+                buffers.normals.push(Vec3::new(x, y, z));
+            }
+            "vt" => {
+                let u: f64 = parse_float(args.first().copied()).map_err(err)?;
+                let v: f64 = match args.get(1) {
+                    Some(v) => parse_float(Some(v)).map_err(err)?,
+                    None => 0.0,
+                };
+                buffers.texcoords.push((u, v));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(err(format!(
+                        "face needs at least 3 vertices, found {}",
+                        args.len()
+                    )));
+                }
+                let corners: Vec<MeshVertex> = args
+                    .iter()
+                    .map(|corner| parse_corner(corner, &buffers))
+                    .collect::<Result<_, _>>()
+                    .map_err(err)?;
+
+                // Fan triangulation, which is exact for the convex polygons exporters emit.
+                for k in 1..corners.len() - 1 {
+                    faces.push(MeshFace {
+                        vertices: [corners[0], corners[k], corners[k + 1]],
+                        mat: current_mat.clone(),
+                    });
+                }
+            }
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(err("mtllib needs a file name".to_string()));
+                }
+                let dir: &Path = path.parent().unwrap_or(Path::new(""));
+                for name in args {
+                    let library: PathBuf = dir.join(name);
+                    materials.extend(load_mtl(&library)?);
+                    libraries.push(library);
+                }
+            }
+            "usemtl" => {
+                let name: String = args.join(" ");
+                current_mat = materials
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| err(format!("unknown material '{}'", name)))?;
+            }
+            // Grouping, smoothing groups and free-form geometry do not affect the mesh.
+            _ => {}
+        }
+    }
+
+    Ok((TriangleMesh::new(buffers, faces), libraries))
+}
+
+// Surface properties read from a `newmtl` block of an MTL file.
+struct MtlProperties {
+    kd: Color,
+    ks: Color,
+    ke: Color,
+    ns: f64,
+    ni: Option<f64>,
+    dissolve: f64,
+    illum: Option<u32>,
+}
+
+impl Default for MtlProperties {
+    fn default() -> Self {
+        Self {
+            kd: Color::new(0.8, 0.8, 0.8),
+            ks: Color::default(),
+            ke: Color::default(),
+            ns: 0.0,
+            ni: None,
+            dissolve: 1.0,
+            illum: None,
+        }
+    }
+}
+
+impl MtlProperties {
+    fn to_material(&self) -> Material {
+        // Emissive materials become lights, transparent materials (dissolve or a refraction
+        // illumination model) become glass, materials whose specular color dominates become metal,
+        // and the rest are diffuse.
+        let transparent: bool = self.dissolve < 1.0 || matches!(self.illum, Some(4 | 6 | 7 | 9));
+        let max_component = |c: Color| c.x().max(c.y()).max(c.z());
+        let specular: bool =
+            self.illum == Some(3) || max_component(self.ks) > max_component(self.kd);
+
+        if max_component(self.ke) > 0.0 {
+            return Material::DiffuseLight(DiffuseLight::new(self.ke));
+        }
+        match (transparent, specular) {
+            (true, _) => Material::Dielectric(Dielectric::new(self.ni.unwrap_or(1.5))),
+            (false, true) => {
+                // Map the Phong exponent onto a roughness-like fuzz in [0, 1].
+                let fuzz: f64 = (2.0 / (self.ns.max(0.0) + 2.0)).sqrt().min(1.0);
+                Material::Metal(Metal::new(self.ks, fuzz))
+            }
+            (false, false) => Material::Lambertian(Lambertian::new(self.kd)),
+        }
+    }
+}
+
+fn load_mtl(path: &Path) -> Result<HashMap<String, Arc<Material>>, LoadError> {
+    let source: String = std::fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+
+    let mut parsed: Vec<(String, MtlProperties)> = Vec::new();
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no: usize = idx + 1;
+        let err = |message: String| LoadError::parse(path, line_no, message);
+        let mut tokens = strip_comment(raw_line).split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(err("newmtl needs a material name".to_string()));
+            }
+            parsed.push((args.join(" "), MtlProperties::default()));
+            continue;
+        }
+
+        let Some((_, props)) = parsed.last_mut() else {
+            // Statements before the first newmtl have nothing to apply to.
+            continue;
+        };
+        match keyword {
+            "Kd" => props.kd = parse_mtl_color(&args).map_err(err)?,
+            "Ks" => props.ks = parse_mtl_color(&args).map_err(err)?,
+            "Ke" => props.ke = parse_mtl_color(&args).map_err(err)?,
+            "Ns" => props.ns = parse_float(args.first().copied()).map_err(err)?,
+            "Ni" => props.ni = Some(parse_float(args.first().copied()).map_err(err)?),
+            "d" => props.dissolve = parse_float(args.last().copied()).map_err(err)?,
+            "Tr" => props.dissolve = 1.0 - parse_float(args.last().copied()).map_err(err)?,
+            "illum" => {
+                let model: &str = args.first().copied().unwrap_or_default();
+                props.illum = Some(
+                    model
+                        .parse()
+                        .map_err(|_| err(format!("invalid illumination model '{}'", model)))?,
+                );
+            }
+            // Texture maps and the remaining Phong terms have no equivalent material parameter.
+            _ => {}
+        }
+    }
+
+    Ok(parsed
+        .into_iter()
+        .map(|(name, props)| (name, Arc::new(props.to_material())))
+        .collect())
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+fn parse_float(token: Option<&str>) -> Result<f64, String> {
+    let token: &str = token.ok_or_else(|| "missing number".to_string())?;
+    token
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", token))
+}
+
+fn parse_floats<const N: usize>(args: &[&str]) -> Result<[f64; N], String> {
+    if args.len() < N {
+        return Err(format!("expected {} numbers, found {}", N, args.len()));
+    }
+    let mut values = [0.0; N];
+    for (value, token) in values.iter_mut().zip(args) {
+        *value = parse_float(Some(token))?;
+    }
+    Ok(values)
+}
+
+fn parse_mtl_color(args: &[&str]) -> Result<Color, String> {
+    match args {
+        [first, ..] if first.parse::<f64>().is_err() => {
+            Err(format!("unsupported color specification '{}'", first))
+        }
+        // A single value is a grey level.
+        [r] => {
+            let r: f64 = parse_float(Some(r))?;
+            Ok(Color::new(r, r, r))
+        }
+        _ => {
+            let [r, g, b] = parse_floats::<3>(args)?;
+            Ok(Color::new(r, g, b))
+        }
+    }
+}
+
+fn parse_corner(corner: &str, buffers: &MeshBuffers) -> Result<MeshVertex, String> {
+    // Corners are `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+    let mut parts = corner.split('/');
+    let position: usize = resolve_index(parts.next(), buffers.positions.len(), "vertex")?
+        .ok_or_else(|| format!("face corner '{}' has no vertex index", corner))?;
+    let texcoord: Option<usize> =
+        resolve_index(parts.next(), buffers.texcoords.len(), "texture coordinate")?;
+    let normal: Option<usize> = resolve_index(parts.next(), buffers.normals.len(), "normal")?;
+
+    Ok(MeshVertex {
+        position,
+        normal,
+        texcoord,
+    })
+}
+
+fn resolve_index(token: Option<&str>, len: usize, kind: &str) -> Result<Option<usize>, String> {
+    // OBJ indices are 1-based, and negative indices count back from the last element read.
+    let token: &str = match token {
+        None | Some("") => return Ok(None),
+        Some(token) => token,
+    };
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid {} index '{}'", kind, token))?;
+    let resolved: i64 = match index {
+        i if i > 0 => i - 1,
+        i if i < 0 => len as i64 + i,
+        _ => return Err(format!("{} index cannot be 0", kind)),
+    };
+    match resolved >= 0 && (resolved as usize) < len {
+        true => Ok(Some(resolved as usize)),
+        false => Err(format!(
+            "{} index {} is out of range ({} defined)",
+            kind, index, len
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loaders::test_file;
+
+    fn error_line(name: &str, source: &str) -> (usize, String) {
+        let path: PathBuf = test_file(name, source.as_bytes());
+        match load_obj(
+            &path,
+            Material::Lambertian(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        ) {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            Err(e) => panic!("expected a parse error, got {}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_line() {
+        let cases: [(&str, &str, usize); 5] = [
+            ("bad_number.obj", "v 0 0 0\nv 1 x 0\n", 2),
+            ("short_face.obj", "v 0 0 0\nv 1 0 0\n\nf 1 2\n", 4),
+            (
+                "bad_index.obj",
+                "v 0 0 0\nv 1 0 0\n# comment\nv 0 1 0\nf 1 2 4\n",
+                5,
+            ),
+            ("unknown_mtl.obj", "v 0 0 0\nusemtl missing\n", 2),
+            ("missing_mtllib.obj", "\n\nmtllib\n", 3),
+        ];
+        for (name, source, expected) in cases {
+            let (line, message) = error_line(name, source);
+            assert_eq!(line, expected, "{}: {}", name, message);
+        }
+    }
+
+    #[test]
+    fn material_library_errors_point_into_the_library() {
+        test_file("broken.mtl", b"newmtl red\nKd 1 0 0\nillum x\n");
+        let path: PathBuf = test_file("uses_broken.obj", b"mtllib broken.mtl\n");
+        match load_obj(
+            &path,
+            Material::Lambertian(Lambertian::new(Color::default())),
+        ) {
+            Err(LoadError::Parse { path, line, .. }) => {
+                assert!(path.ends_with("broken.mtl"));
+                assert_eq!(line, 3);
+            }
+            _ => panic!("expected a parse error in the material library"),
+        }
+    }
+
+    #[test]
+    fn reports_the_material_libraries_it_reads() {
+        let library: PathBuf = test_file("red.mtl", b"newmtl red\nKd 1 0 0\n");
+        let path: PathBuf = test_file(
+            "uses_red.obj",
+            b"mtllib red.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n",
+        );
+        let (_, libraries) = load_obj_with_libraries(
+            &path,
+            Material::Lambertian(Lambertian::new(Color::default())),
+        )
+        .unwrap();
+        assert_eq!(libraries, vec![library]);
+    }
+}
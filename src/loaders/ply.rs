@@ -0,0 +1,546 @@
+use std::{path::Path, sync::Arc};
+
+use super::LoadError;
+use crate::{
+    color::Color,
+    hittables::mesh::{MeshBuffers, MeshFace, MeshVertex, TriangleMesh},
+    materials::{lambertian::Lambertian, material::Material},
+    ray::Point3,
+    vec3::Vec3,
+};
+
+// Loads a Stanford PLY file (ASCII, binary little endian or binary big endian) into a triangle
+// mesh. Per-vertex normals are used for smooth shading. When the vertices carry colors, the mesh
+// is Lambertian with the colors interpolated across each face; otherwise it uses
+// `default_material`.
+pub fn load_ply(
+    path: impl AsRef<Path>,
+    default_material: Material,
+) -> Result<TriangleMesh, LoadError> {
+    let path: &Path = path.as_ref();
+    let data: Vec<u8> = std::fs::read(path).map_err(|e| LoadError::io(path, e))?;
+
+    let header: Header =
+        parse_header(&data).map_err(|(line, msg)| LoadError::parse(path, line, msg))?;
+    let mut reader: BodyReader = match header.format {
+        Format::Ascii => BodyReader::ascii(&data[header.body_offset..], header.line_count + 1),
+        Format::BinaryLittleEndian => BodyReader::binary(&data[header.body_offset..], false),
+        Format::BinaryBigEndian => BodyReader::binary(&data[header.body_offset..], true),
+    };
+
+    let mut buffers: MeshBuffers = MeshBuffers::default();
+    let mut polygons: Vec<Vec<usize>> = Vec::new();
+    for element in &header.elements {
+        // ASCII errors point at the offending body line, binary errors at the element declaration.
+        let err = |reader: &BodyReader, message: String| {
+            LoadError::parse(path, reader.line().unwrap_or(element.line), message)
+        };
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, &mut reader, &mut buffers)
+                .map_err(|msg| err(&reader, msg))?,
+            "face" => {
+                read_faces(element, &mut reader, &mut polygons).map_err(|msg| err(&reader, msg))?
+            }
+            // An element without properties takes no space, however large its count.
+            _ if element.properties.is_empty() => {}
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        reader.skip(property).map_err(|msg| err(&reader, msg))?;
+                    }
+                }
+            }
+        }
+    }
+
+    let mat: Arc<Material> = match buffers.colors.is_empty() {
+        true => Arc::new(default_material),
+        false => Arc::new(Material::Lambertian(Lambertian::vertex_colored())),
+    };
+    let vertex_count: usize = buffers.positions.len();
+    let mut faces: Vec<MeshFace> = Vec::with_capacity(polygons.len());
+    for (face_idx, polygon) in polygons.iter().enumerate() {
+        if let Some(&bad) = polygon.iter().find(|&&i| i >= vertex_count) {
+            return Err(LoadError::parse(
+                path,
+                header.face_line,
+                format!(
+                    "face {} references vertex {} but only {} are defined",
+                    face_idx, bad, vertex_count
+                ),
+            ));
+        }
+        let corners: Vec<MeshVertex> = polygon
+            .iter()
+            .map(|&i| MeshVertex {
+                normal: (!buffers.normals.is_empty()).then_some(i),
+                ..MeshVertex::new(i)
+            })
+            .collect();
+
+        // Fan triangulation, which is exact for the convex polygons scanners emit.
+        for k in 1..corners.len().saturating_sub(1) {
+            faces.push(MeshFace {
+                vertices: [corners[0], corners[k], corners[k + 1]],
+                mat: mat.clone(),
+            });
+        }
+    }
+
+    Ok(TriangleMesh::new(buffers, faces))
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(Self::I8),
+            "uchar" | "uint8" => Some(Self::U8),
+            "short" | "int16" => Some(Self::I16),
+            "ushort" | "uint16" => Some(Self::U16),
+            "int" | "int32" => Some(Self::I32),
+            "uint" | "uint32" => Some(Self::U32),
+            "float" | "float32" => Some(Self::F32),
+            "double" | "float64" => Some(Self::F64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+}
+
+enum PropertyKind {
+    Scalar(ScalarType),
+    List { count: ScalarType, item: ScalarType },
+}
+
+struct Property {
+    name: String,
+    kind: PropertyKind,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+    line: usize,
+}
+
+struct Header {
+    format: Format,
+    elements: Vec<Element>,
+    body_offset: usize,
+    line_count: usize,
+    face_line: usize,
+}
+
+fn parse_header(data: &[u8]) -> Result<Header, (usize, String)> {
+    let mut format: Option<Format> = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut offset: usize = 0;
+    let mut line_no: usize = 0;
+
+    loop {
+        let rest: &[u8] = &data[offset..];
+        let Some(len) = rest.iter().position(|&b| b == b'\n') else {
+            return Err((line_no + 1, "header is missing end_header".to_string()));
+        };
+        offset += len + 1;
+        line_no += 1;
+        let line: &str = std::str::from_utf8(&rest[..len])
+            .map_err(|_| (line_no, "header is not valid text".to_string()))?
+            .trim_end_matches('\r');
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["ply"] if line_no == 1 => {}
+            _ if line_no == 1 => return Err((1, "not a PLY file".to_string())),
+            ["format", kind, _version] => {
+                format = Some(match *kind {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    other => return Err((line_no, format!("unknown format '{}'", other))),
+                })
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| (line_no, format!("invalid element count '{}'", count)))?,
+                properties: Vec::new(),
+                line: line_no,
+            }),
+            ["property", rest @ ..] => {
+                let element: &mut Element = elements
+                    .last_mut()
+                    .ok_or((line_no, "property declared before any element".to_string()))?;
+                let scalar = |name: &str| {
+                    ScalarType::from_name(name)
+                        .ok_or((line_no, format!("unknown property type '{}'", name)))
+                };
+                let property: Property = match rest {
+                    ["list", count, item, name] => Property {
+                        name: name.to_string(),
+                        kind: PropertyKind::List {
+                            count: scalar(count)?,
+                            item: scalar(item)?,
+                        },
+                    },
+                    [ty, name] => Property {
+                        name: name.to_string(),
+                        kind: PropertyKind::Scalar(scalar(ty)?),
+                    },
+                    _ => return Err((line_no, format!("malformed property '{}'", line))),
+                };
+                element.properties.push(property);
+            }
+            ["end_header"] => break,
+            _ => return Err((line_no, format!("unexpected header line '{}'", line))),
+        }
+    }
+
+    let format: Format = format.ok_or((line_no, "header has no format line".to_string()))?;
+    let face_line: usize = elements
+        .iter()
+        .find(|e| e.name == "face")
+        .map_or(line_no, |e| e.line);
+    Ok(Header {
+        format,
+        elements,
+        body_offset: offset,
+        line_count: line_no,
+        face_line,
+    })
+}
+
+fn read_vertices(
+    element: &Element,
+    reader: &mut BodyReader,
+    buffers: &mut MeshBuffers,
+) -> Result<(), String> {
+    let index_of = |names: &[&str]| {
+        element
+            .properties
+            .iter()
+            .position(|p| names.contains(&p.name.as_str()))
+    };
+    let position_idx: [usize; 3] = match (index_of(&["x"]), index_of(&["y"]), index_of(&["z"])) {
+        (Some(x), Some(y), Some(z)) => [x, y, z],
+        _ => return Err("vertex element needs x, y and z properties".to_string()),
+    };
+    let normal_idx: Option<[usize; 3]> =
+        match (index_of(&["nx"]), index_of(&["ny"]), index_of(&["nz"])) {
+            (Some(x), Some(y), Some(z)) => Some([x, y, z]),
+            _ => None,
+        };
+    let color_idx: Option<[usize; 3]> = match (
+        index_of(&["red", "r", "diffuse_red"]),
+        index_of(&["green", "g", "diffuse_green"]),
+        index_of(&["blue", "b", "diffuse_blue"]),
+    ) {
+        (Some(r), Some(g), Some(b)) => Some([r, g, b]),
+        _ => None,
+    };
+
+    let mut values: Vec<f64> = vec![0.0; element.properties.len()];
+    for _ in 0..element.count {
+        for (value, property) in values.iter_mut().zip(&element.properties) {
+            *value = match property.kind {
+                PropertyKind::Scalar(ty) => reader.read(ty)?,
+                PropertyKind::List { .. } => {
+                    reader.skip(property)?;
+                    0.0
+                }
+            };
+        }
+        let [x, y, z] = position_idx.map(|i| values[i]);
+        buffers.positions.push(Point3::new(x, y, z));
+        if let Some(idx) = normal_idx {
+            let [x, y, z] = idx.map(|i| values[i]);
+            buffers.normals.push(Vec3::new(x, y, z));
+        }
+        if let Some(idx) = color_idx {
+            // Integer channels are 8-bit; floating point channels are already in [0, 1].
+            let [r, g, b] = idx.map(|i| match element.properties[i].kind {
+                PropertyKind::Scalar(ScalarType::F32 | ScalarType::F64) => values[i],
+                _ => values[i] / 255.0,
+            });
+            buffers.colors.push(Color::new(r, g, b));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_faces(
+    element: &Element,
+    reader: &mut BodyReader,
+    polygons: &mut Vec<Vec<usize>>,
+) -> Result<(), String> {
+    let indices_idx: usize = element
+        .properties
+        .iter()
+        .position(|p| {
+            matches!(p.kind, PropertyKind::List { .. })
+                && (p.name == "vertex_indices" || p.name == "vertex_index")
+        })
+        .ok_or_else(|| "face element needs a vertex_indices list".to_string())?;
+
+    // Counts come from the file, so nothing is reserved from them: a count larger than the data
+    // runs out of data and fails instead of allocating.
+    for _ in 0..element.count {
+        for (i, property) in element.properties.iter().enumerate() {
+            match (&property.kind, i == indices_idx) {
+                (PropertyKind::List { count, item }, true) => {
+                    let n: usize = reader.read(*count)? as usize;
+                    let mut polygon: Vec<usize> = Vec::new();
+                    for _ in 0..n {
+                        let index: f64 = reader.read(*item)?;
+                        if index < 0.0 {
+                            return Err(format!("negative vertex index {}", index));
+                        }
+                        polygon.push(index as usize);
+                    }
+                    polygons.push(polygon);
+                }
+                _ => reader.skip(property)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+enum BodyReader<'d> {
+    Ascii {
+        lines: std::iter::Enumerate<std::str::Lines<'d>>,
+        tokens: std::str::SplitWhitespace<'d>,
+        first_line: usize,
+        line: usize,
+    },
+    Binary {
+        data: &'d [u8],
+        pos: usize,
+        big_endian: bool,
+    },
+}
+
+impl<'d> BodyReader<'d> {
+    fn ascii(data: &'d [u8], first_line: usize) -> Self {
+        // Non UTF-8 bodies are malformed ASCII files; surface that as a missing-data error.
+        let text: &'d str = std::str::from_utf8(data).unwrap_or_default();
+        Self::Ascii {
+            lines: text.lines().enumerate(),
+            tokens: "".split_whitespace(),
+            first_line,
+            line: first_line,
+        }
+    }
+
+    fn binary(data: &'d [u8], big_endian: bool) -> Self {
+        Self::Binary {
+            data,
+            pos: 0,
+            big_endian,
+        }
+    }
+
+    fn line(&self) -> Option<usize> {
+        match self {
+            Self::Ascii { line, .. } => Some(*line),
+            Self::Binary { .. } => None,
+        }
+    }
+
+    fn read(&mut self, ty: ScalarType) -> Result<f64, String> {
+        match self {
+            Self::Ascii {
+                lines,
+                tokens,
+                first_line,
+                line,
+            } => {
+                let token: &str = loop {
+                    if let Some(token) = tokens.next() {
+                        break token;
+                    }
+                    let (idx, next) = lines.next().ok_or("unexpected end of data")?;
+                    *line = *first_line + idx;
+                    *tokens = next.split_whitespace();
+                };
+                let value: f64 = token
+                    .parse()
+                    .map_err(|_| format!("invalid number '{}'", token))?;
+                match ty {
+                    ScalarType::F32 | ScalarType::F64 => Ok(value),
+                    _ if value.fract() == 0.0 => Ok(value),
+                    _ => Err(format!("expected an integer, found '{}'", token)),
+                }
+            }
+            Self::Binary {
+                data,
+                pos,
+                big_endian,
+            } => {
+                let size: usize = ty.size();
+                let bytes: &[u8] = data
+                    .get(*pos..*pos + size)
+                    .ok_or_else(|| format!("unexpected end of data at byte {}", *pos))?;
+                *pos += size;
+
+                let mut buf = [0u8; 8];
+                buf[..size].copy_from_slice(bytes);
+                if *big_endian {
+                    buf[..size].reverse();
+                }
+                Ok(match ty {
+                    ScalarType::I8 => buf[0] as i8 as f64,
+                    ScalarType::U8 => buf[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::U32 => u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::F32 => f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(buf),
+                })
+            }
+        }
+    }
+
+    fn skip(&mut self, property: &Property) -> Result<(), String> {
+        match property.kind {
+            PropertyKind::Scalar(ty) => {
+                self.read(ty)?;
+            }
+            PropertyKind::List { count, item } => {
+                let n: usize = self.read(count)? as usize;
+                for _ in 0..n {
+                    self.read(item)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::loaders::test_file;
+
+    const HEADER: &str = "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+    fn error_line(name: &str, data: &[u8]) -> (usize, String) {
+        let path: PathBuf = test_file(name, data);
+        match load_ply(
+            &path,
+            Material::Lambertian(Lambertian::new(Color::default())),
+        ) {
+            Err(LoadError::Parse { line, message, .. }) => (line, message),
+            Err(e) => panic!("expected a parse error, got {}", e),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn header_errors_point_at_the_header_line() {
+        let cases: [(&str, String, usize); 4] = [
+            ("not_ply.ply", "obj\n".to_string(), 1),
+            (
+                "bad_format.ply",
+                "ply\nformat text 1.0\nend_header\n".to_string(),
+                2,
+            ),
+            (
+                "bad_type.ply",
+                HEADER.replace("property float y", "property real y"),
+                5,
+            ),
+            ("no_end.ply", "ply\nformat ascii 1.0\n".to_string(), 3),
+        ];
+        for (name, source, expected) in cases {
+            let (line, message) = error_line(name, source.as_bytes());
+            assert_eq!(line, expected, "{}: {}", name, message);
+        }
+    }
+
+    #[test]
+    fn ascii_body_errors_point_at_the_body_line() {
+        // Running out of data points at the last line there is.
+        let cases: [(&str, &str, usize); 3] = [
+            ("bad_number.ply", "0 0 0\n1 0 x\n0 1 0\n3 0 1 2\n", 11),
+            ("short_data.ply", "0 0 0\n1 0 0\n", 11),
+            ("negative_index.ply", "0 0 0\n1 0 0\n0 1 0\n3 0 -1 2\n", 13),
+        ];
+        for (name, body, expected) in cases {
+            let (line, message) = error_line(name, format!("{}{}", HEADER, body).as_bytes());
+            assert_eq!(line, expected, "{}: {}", name, message);
+        }
+    }
+
+    #[test]
+    fn face_index_errors_point_at_the_face_element() {
+        let body: &str = "0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n";
+        let (line, message) = error_line("bad_index.ply", format!("{}{}", HEADER, body).as_bytes());
+        assert_eq!(line, 7, "{}", message);
+    }
+
+    #[test]
+    fn binary_errors_point_at_the_element() {
+        // Two of the three vertices, then the data ends.
+        let mut data: Vec<u8> = HEADER.replace("ascii", "binary_little_endian").into_bytes();
+        for v in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        let (line, message) = error_line("truncated.ply", &data);
+        assert_eq!(line, 3, "{}", message);
+    }
+
+    #[test]
+    fn counts_larger_than_the_data_fail_instead_of_allocating() {
+        let header: String = HEADER.replace("element face 1", "element face 4000000000000");
+        let (line, message) = error_line(
+            "huge_count.ply",
+            format!("{}0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n", header).as_bytes(),
+        );
+        assert_eq!(line, 13, "{}", message);
+    }
+}
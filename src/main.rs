@@ -1,106 +1,227 @@
-pub mod camera;
-pub mod color;
-pub mod hittables;
-pub mod interval;
-pub mod materials;
-pub mod ray;
-pub mod util;
-pub mod vec3;
+mod cli;
 
-use camera::{Camera, CameraConfig};
-use color::Color;
-use materials::{dielectric::Dielectric, lambertian::Lambertian, material::Material, metal::Metal};
-use ray::Point3;
-use util::{random_double, random_double_range};
-use vec3::Vec3;
+use clap::Parser;
+use cli::Args;
+use ray_tracing_in_one_weekend::{
+    Accumulator, CancellationToken, Checkpoint, Framebuffer, ImageWriter, Progress,
+    ProgressObserver, RenderOutput, Scene,
+};
 
-use crate::hittables::{hittable::HittableType, hittable_list::HittableList, sphere::Sphere};
-
-use std::sync::Arc;
+use std::{
+    error::Error,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
 
 fn main() {
-    let mut world: HittableList = HittableList::default();
+    let args: Args = Args::parse();
+    if let Err(e) = run(&args) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
 
-    let ground_material: Material =
-        Material::Lambertian(Lambertian::new(Color::new(0.5, 0.5, 0.5)));
-    let sphere_ground: HittableType = HittableType::Sphere(Arc::new(Sphere::new(
-        &Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material,
-    )));
-    world.add(sphere_ground);
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    // Validate everything cheap before spending time on the scene.
+    let writer = args.image_writer()?;
+    let heatmap_writer: Option<ImageWriter> = args.heatmap_writer()?;
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .build_global()?;
+    }
 
-    let fixed_point: &Point3 = &Point3::new(4.0, 0.2, 0.0);
-    for a in -11..11 {
-        for b in -11..11 {
-            let rand_double: f64 = random_double();
-            let center: Point3 = Point3::new(
-                a as f64 + 0.9 * random_double(),
-                0.2,
-                b as f64 + 0.9 * random_double(),
-            );
-            if (center - *fixed_point).length() <= 0.9 {
-                continue;
-            }
+    let mut scene: Scene = args.load_scene()?;
+    args.apply(&mut scene.camera_config);
+    if args.min_spp.is_some() && scene.camera_config.adaptive.is_none() {
+        return Err("--min-spp needs adaptive sampling, from --adaptive or the scene".into());
+    }
+    let output_error = |e: io::Error| format!("{}: {}", args.output.display(), e);
+    // Check the output can be written so a bad path fails before the render rather than after,
+    // without touching the image already there until the new one is complete.
+    check_writable(&args.output).map_err(output_error)?;
 
-            let sphere: HittableType = match rand_double {
-                rd if rd < 0.8 => {
-                    // diffuse
-                    let albedo: Color = Color::random() * Color::random();
-                    let material: Material = Material::Lambertian(Lambertian::new(albedo));
-                    HittableType::Sphere(Arc::new(Sphere::new(&center, 0.2, material)))
-                }
-                rd if rd < 0.95 => {
-                    // metal
-                    let albedo: Color = Color::random_range(0.5, 1.0);
-                    let fuzz: f64 = random_double_range(0.0, 0.5);
-                    let material: Material = Material::Metal(Metal::new(albedo, fuzz));
-                    HittableType::Sphere(Arc::new(Sphere::new(&center, 0.2, material)))
+    let cancel: CancellationToken = CancellationToken::new();
+    if let Some(limit) = args.time_limit {
+        let cancel: CancellationToken = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs_f64(limit));
+            cancel.cancel();
+        });
+    }
+    let (width, height) = scene.camera().image_size();
+    let seed: u64 = scene.camera_config.seed;
+    let scene_samples_per_pixel: u32 = scene.camera_config.samples_per_pixel as u32;
+    let scene_id: u64 = args.scene_id(&scene)?;
+    let checkpoint_error = |path: &Path, e: io::Error| format!("{}: {}", path.display(), e);
+    let mut accumulator: Accumulator = Accumulator::new(width, height);
+    if let (true, Some(path)) = (args.resume, &args.checkpoint) {
+        let checkpoint: Checkpoint =
+            Checkpoint::load(path).map_err(|e| checkpoint_error(path, e))?;
+        let saved: &Accumulator = &checkpoint.accumulator;
+        if (saved.width(), saved.height()) != (width, height) {
+            return Err(format!(
+                "{}: checkpoint is {}x{} but the render is {}x{}",
+                path.display(),
+                saved.width(),
+                saved.height(),
+                width,
+                height
+            )
+            .into());
+        }
+        if checkpoint.seed != seed {
+            return Err(format!(
+                "{}: checkpoint was rendered with --seed {}",
+                path.display(),
+                checkpoint.seed
+            )
+            .into());
+        }
+        if checkpoint.scene_id != scene_id {
+            return Err(format!(
+                "{}: checkpoint was made for a different scene or camera",
+                path.display()
+            )
+            .into());
+        }
+        accumulator = checkpoint.accumulator;
+    }
+    let save_checkpoint = |path: &Path, accumulator: &Accumulator| {
+        let checkpoint: Checkpoint = Checkpoint {
+            seed,
+            scene_id,
+            accumulator: accumulator.clone(),
+        };
+        checkpoint.save(path).map_err(|e| checkpoint_error(path, e))
+    };
+
+    // Checkpoints can only be taken between passes, so checkpointing alone renders in passes of
+    // a single sample.
+    let samples_per_pass: Option<u32> = args.progressive.or(args.checkpoint.as_ref().map(|_| 1));
+    let render: RenderOutput = match samples_per_pass {
+        None => scene.render_with(&ConsoleProgress, &cancel),
+        Some(samples_per_pass) => {
+            // Snapshot every N passes, every so many seconds, or both; every 10 seconds if neither
+            // is asked for.
+            let interval: Option<Duration> = match (args.snapshot_passes, args.snapshot_interval) {
+                (None, None) => Some(Duration::from_secs(10)),
+                (_, seconds) => seconds.map(Duration::from_secs_f64),
+            };
+            let checkpoint_interval: Duration = Duration::from_secs_f64(args.checkpoint_interval);
+            let mut last_snapshot: Instant = Instant::now();
+            let mut last_checkpoint: Instant = Instant::now();
+            let mut on_pass = |pass: u32, accumulator: &Accumulator| {
+                let snapshot_due: bool = args.progressive.is_some()
+                    && (args.snapshot_passes.is_some_and(|n| pass.is_multiple_of(n))
+                        || interval.is_some_and(|i| last_snapshot.elapsed() >= i));
+                if snapshot_due {
+                    if let Err(e) = write_image(&args.output, &writer, &accumulator.resolve()) {
+                        eprintln!("\nwarning: snapshot {}: {}", args.output.display(), e);
+                    }
+                    last_snapshot = Instant::now();
                 }
-                _ => {
-                    // glass
-                    let material: Material = Material::Dielectric(Dielectric::new(1.5));
-                    HittableType::Sphere(Arc::new(Sphere::new(&center, 0.2, material)))
+                if let Some(path) = &args.checkpoint {
+                    if last_checkpoint.elapsed() >= checkpoint_interval {
+                        if let Err(e) = save_checkpoint(path, accumulator) {
+                            eprintln!("\nwarning: {}", e);
+                        }
+                        last_checkpoint = Instant::now();
+                    }
                 }
             };
-
-            world.add(sphere);
+            scene.resume(
+                accumulator,
+                samples_per_pass,
+                &ConsoleProgress,
+                &cancel,
+                &mut on_pass,
+            )?
         }
+    };
+    if let Some(path) = &args.checkpoint {
+        save_checkpoint(path, &render.accumulator)?;
+    }
+    if render.cancelled {
+        println!(
+            "Time limit reached; writing the partial image ({:.1}% rendered).",
+            render.progress.percent()
+        );
     }
 
-    let material1: Material = Material::Dielectric(Dielectric::new(1.5));
-    world.add(HittableType::Sphere(Arc::new(Sphere::new(
-        &Point3::new(0.0, 1.0, 0.0),
-        1.0,
-        material1,
-    ))));
+    let write_sw = SystemTime::now();
+    write_image(&args.output, &writer, &render.image).map_err(output_error)?;
+    if let (Some(path), Some(heatmap_writer)) = (&args.sample_heatmap, &heatmap_writer) {
+        let heatmap: Framebuffer = render.accumulator.sample_heatmap(scene_samples_per_pixel);
+        write_image(path, heatmap_writer, &heatmap)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    println!(
+        "Write time: {}",
+        format_duration(write_sw.elapsed().unwrap())
+    );
+    println!("Done.");
+    Ok(())
+}
 
-    let material2: Material = Material::Lambertian(Lambertian::new(Color::new(0.4, 0.2, 0.1)));
-    world.add(HittableType::Sphere(Arc::new(Sphere::new(
-        &Color::new(-4.0, 1.0, 0.0),
-        1.0,
-        material2,
-    ))));
+// Writes the whole image in one pass through a temporary sibling file, so that anything watching
+// the output never sees a half-written image.
+fn write_image(path: &Path, writer: &ImageWriter, image: &Framebuffer) -> io::Result<()> {
+    let temp: PathBuf = temp_path(path);
+    let mut buff = BufWriter::new(File::create(&temp)?);
+    writer.write(&mut buff, image)?;
+    buff.into_inner()?.sync_all()?;
+    fs::rename(&temp, path)
+}
 
-    let material3: Material = Material::Metal(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
-    world.add(HittableType::Sphere(Arc::new(Sphere::new(
-        &Color::new(4.0, 1.0, 0.0),
-        1.0,
-        material3,
-    ))));
+// Creates and removes the temporary file `write_image` would write `path` through.
+fn check_writable(path: &Path) -> io::Result<()> {
+    let temp: PathBuf = temp_path(path);
+    File::create(&temp)?;
+    fs::remove_file(&temp)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp: OsString = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    PathBuf::from(temp)
+}
 
-    let camera = Camera::new(CameraConfig {
-        aspect_ratio: 16.0 / 9.0,
-        image_width: 1200.0,
-        samples_per_pixel: 500.0,
-        max_depth: 50,
-        vfov: 20.0,
-        look_from: Point3::new(13.0, 2.0, 3.0),
-        look_at: Point3::new(0.0, 0.0, 0.0),
-        v_up: Vec3::new(0.0, 0.2, 0.0),
-        defocus_angle: 0.6,
-        focus_dist: 10.0,
-    });
+// Keeps a single status line on stdout up to date.
+struct ConsoleProgress;
 
-    camera.render(HittableType::List(Arc::new(world)));
+impl ProgressObserver for ConsoleProgress {
+    fn on_progress(&self, progress: &Progress) {
+        let eta: String = progress.eta().map_or("-".to_string(), format_duration);
+        print!(
+            "\r|Render| {:6.2}%  elapsed {}  eta {}  {:.2} Msamples/s   ",
+            progress.percent(),
+            format_duration(progress.elapsed),
+            eta,
+            progress.samples_per_second() / 1e6
+        );
+        let _ = io::stdout().flush();
+    }
+
+    fn on_finish(&self, progress: &Progress, _cancelled: bool) {
+        println!();
+        println!(
+            "Render time: {} ({:.2} Msamples/s)",
+            format_duration(progress.elapsed),
+            progress.samples_per_second() / 1e6
+        );
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let secs: u64 = d.as_secs();
+    match secs {
+        0..=59 => format!("{:.1}s", d.as_secs_f64()),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m{:02}s", secs / 3600, secs / 60 % 60, secs % 60),
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
     color::Color,
     ray::Ray,
-    util::random_double,
+    sampler::Sampler,
     vec3::{dot, reflect, refract, unit_vector, Vec3},
 };
 
@@ -31,6 +31,7 @@ impl Scatter for Dielectric {
         rec: &crate::hittables::hittable::HitRecord,
         attenuation: &mut crate::color::Color,
         scattered: &mut crate::ray::Ray,
+        sampler: &mut Sampler,
     ) -> bool {
         *attenuation = Color::new(1.0, 1.0, 1.0);
         let ri: f64 = match rec.front_face {
@@ -42,7 +43,7 @@ impl Scatter for Dielectric {
         let sin_theta: f64 = (1.0 - cos_theta.powi(2)).sqrt();
         let cannot_refract = ri * sin_theta > 1.0;
         let direction: Vec3 =
-            match cannot_refract || Self::reflectance(cos_theta, ri) > random_double() {
+            match cannot_refract || Self::reflectance(cos_theta, ri) > sampler.get_1d() {
                 true => reflect(&unit_direction, &rec.normal),
                 false => refract(&unit_direction, &rec.normal, ri),
             };
@@ -0,0 +1,32 @@
+use super::material::Scatter;
+use crate::{color::Color, hittables::hittable::HitRecord, ray::Ray, sampler::Sampler};
+
+// Area light: emits the same radiance in every direction from both sides of the surface and
+// reflects nothing.
+#[derive(Clone, Debug)]
+pub struct DiffuseLight {
+    emit: Color,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self { emit }
+    }
+
+    pub fn emitted(&self) -> Color {
+        self.emit
+    }
+}
+
+impl Scatter for DiffuseLight {
+    fn scatter(
+        &self,
+        _r_in: &Ray,
+        _rec: &HitRecord,
+        _attenuation: &mut Color,
+        _scattered: &mut Ray,
+        _sampler: &mut Sampler,
+    ) -> bool {
+        false
+    }
+}
@@ -1,27 +1,65 @@
+use std::f64::consts::PI;
+
 use super::material::Scatter;
 use crate::hittables::hittable::HitRecord;
-use crate::{color::Color, ray::Ray, vec3::random_unit_vector};
+use crate::{
+    color::Color,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{dot, random_unit_vector, unit_vector, Vec3},
+};
+
+// Where a Lambertian surface takes its color from.
+#[derive(Clone, Debug)]
+pub enum Albedo {
+    Solid(Color),
+    // The mesh's vertex colors, interpolated across each triangle; mid grey on other shapes.
+    VertexColor,
+}
 
 #[derive(Clone, Debug)]
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Albedo,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
-        Self { albedo }
+        Self {
+            albedo: Albedo::Solid(albedo),
+        }
+    }
+
+    pub fn vertex_colored() -> Self {
+        Self {
+            albedo: Albedo::VertexColor,
+        }
+    }
+
+    fn albedo(&self, rec: &HitRecord) -> Color {
+        match self.albedo {
+            Albedo::Solid(color) => color,
+            Albedo::VertexColor => rec.color.unwrap_or(Color::new(0.5, 0.5, 0.5)),
+        }
+    }
+
+    // BSDF times cosine towards `direction`, and the density of `scatter` picking it: scattering
+    // is cosine-weighted, so the pdf is cos(theta) / pi.
+    pub fn scattering(&self, rec: &HitRecord, direction: Vec3) -> (Color, f64) {
+        let cosine: f64 = dot(rec.normal, unit_vector(direction)).max(0.0);
+        ((cosine / PI) * self.albedo(rec), cosine / PI)
     }
 }
 
 impl Scatter for Lambertian {
     fn scatter(
         &self,
-        r_in: &crate::ray::Ray,
+        _r_in: &crate::ray::Ray,
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut crate::ray::Ray,
+        sampler: &mut Sampler,
     ) -> bool {
-        let mut scatter_direction = rec.normal + random_unit_vector();
+        let mut scatter_direction = rec.normal + random_unit_vector(sampler);
 
         // Catch degenerate scatter direction
         if scatter_direction.near_zero() {
@@ -29,7 +67,7 @@ impl Scatter for Lambertian {
         }
 
         *scattered = Ray::new(rec.p, scatter_direction);
-        *attenuation = self.albedo;
+        *attenuation = self.albedo(rec);
         true
     }
 }
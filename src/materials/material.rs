@@ -1,12 +1,37 @@
-use crate::{color::Color, hittables::hittable::HitRecord, ray::Ray};
+use crate::{color::Color, hittables::hittable::HitRecord, ray::Ray, sampler::Sampler, vec3::Vec3};
 
-use super::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal};
+use super::{
+    dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian, metal::Metal,
+};
 
 #[derive(Clone, Debug)]
 pub enum Material {
     Metal(Metal),
     Lambertian(Lambertian),
     Dielectric(Dielectric),
+    DiffuseLight(DiffuseLight),
+}
+
+impl Material {
+    // Radiance the surface gives off by itself, on top of whatever it scatters.
+    pub fn emitted(&self) -> Color {
+        match self {
+            Material::DiffuseLight(light) => light.emitted(),
+            _ => Color::default(),
+        }
+    }
+
+    // For materials that scatter light over a spread of directions: the BSDF times the cosine
+    // for light arriving from `direction` and leaving along `r_in` reversed, and the density per
+    // solid angle with which `scatter` picks that direction. None for specular materials, which
+    // can't make use of light samples.
+    pub fn scattering(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<(Color, f64)> {
+        match self {
+            Material::Lambertian(l) => Some(l.scattering(rec, direction)),
+            Material::Metal(m) => m.scattering(r_in, rec, direction),
+            _ => None,
+        }
+    }
 }
 
 impl Default for Material {
@@ -22,5 +47,6 @@ pub trait Scatter {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut Sampler,
     ) -> bool;
 }
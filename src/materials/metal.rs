@@ -1,6 +1,10 @@
+use std::f64::consts::PI;
+
 use crate::{
     color::Color,
+    hittables::hittable::HitRecord,
     ray::Ray,
+    sampler::Sampler,
     vec3::{dot, random_unit_vector, reflect, unit_vector, Vec3},
 };
 
@@ -16,6 +20,38 @@ impl Metal {
     pub fn new(albedo: Color, fuzz: f64) -> Self {
         Self { albedo, fuzz }
     }
+
+    // BSDF times cosine towards `direction`, and the density of `scatter` picking it; None for a
+    // perfect mirror. `scatter` offsets the mirror direction R by a point uniform on a sphere of
+    // radius fuzz, so the density of a direction w is that of the sphere's area where the ray
+    // along w crosses it, s^2 / (|cos| 4 pi fuzz^2) summed over the crossings at distance s.
+    // Directions that end up below the surface are absorbed, so the BSDF times cosine is the
+    // albedo times the density above it and zero below.
+    pub fn scattering(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Option<(Color, f64)> {
+        if self.fuzz <= 0.0 {
+            return None;
+        }
+        let mirror: Vec3 = unit_vector(reflect(&r_in.direction(), &rec.normal));
+        let w: Vec3 = unit_vector(direction);
+        // Crossings of the ray along w with the sphere of radius fuzz about R are at s = b +- q.
+        let b: f64 = dot(w, mirror);
+        let discriminant: f64 = b * b - (1.0 - self.fuzz * self.fuzz);
+        if discriminant <= 0.0 {
+            return Some((Color::default(), 0.0));
+        }
+        let q: f64 = discriminant.sqrt();
+        // Where the ray crosses the sphere, |cos| between it and the sphere's normal is q / fuzz.
+        let pdf: f64 = [b - q, b + q]
+            .iter()
+            .filter(|&&s| s > 0.0)
+            .map(|s| s * s)
+            .sum::<f64>()
+            / (4.0 * PI * self.fuzz * q);
+        match dot(w, rec.normal) > 0.0 {
+            true => Some((pdf * self.albedo, pdf)),
+            false => Some((Color::default(), pdf)),
+        }
+    }
 }
 
 impl Scatter for Metal {
@@ -25,9 +61,10 @@ impl Scatter for Metal {
         rec: &crate::hittables::hittable::HitRecord,
         attenuation: &mut Color,
         scattered: &mut crate::ray::Ray,
+        sampler: &mut Sampler,
     ) -> bool {
         let mut reflected: Vec3 = reflect(&r_in.direction(), &rec.normal);
-        reflected = unit_vector(reflected) + (self.fuzz * random_unit_vector());
+        reflected = unit_vector(reflected) + (self.fuzz * random_unit_vector(sampler));
         *scattered = Ray::new(rec.p, reflected);
         *attenuation = self.albedo;
 
@@ -1,4 +1,5 @@
 pub mod dielectric;
+pub mod diffuse_light;
 pub mod lambertian;
 pub mod material;
 pub mod metal;
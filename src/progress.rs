@@ -0,0 +1,94 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+// Snapshot of how far a render has come.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Progress {
+    pub completed_pixels: usize,
+    pub total_pixels: usize,
+    pub samples: u64,         // Camera samples traced so far
+    pub skipped_samples: u64, // Samples saved by pixels that converged early
+    pub total_samples: u64,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    // Fraction of the samples traced or found unnecessary, in [0, 1].
+    pub fn fraction(&self) -> f64 {
+        match self.total_samples {
+            0 => 1.0,
+            total => (self.samples + self.skipped_samples) as f64 / total as f64,
+        }
+    }
+
+    pub fn percent(&self) -> f64 {
+        100.0 * self.fraction()
+    }
+
+    // Estimated time left, extrapolated from the rate so far; None until some work is done.
+    pub fn eta(&self) -> Option<Duration> {
+        match self.fraction() {
+            f if f <= 0.0 => None,
+            f => Some(self.elapsed.mul_f64((1.0 - f) / f)),
+        }
+    }
+
+    pub fn samples_per_second(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.samples as f64 / secs,
+            _ => 0.0,
+        }
+    }
+}
+
+// Receives progress while a render runs. Calls come from the render threads as tiles finish, so
+// implementations must be cheap and thread safe. Closures taking a `&Progress` are observers.
+pub trait ProgressObserver: Sync {
+    fn on_progress(&self, progress: &Progress);
+
+    // Called once when the render stops, whether it finished or was cancelled.
+    fn on_finish(&self, _progress: &Progress, _cancelled: bool) {}
+}
+
+impl<F> ProgressObserver for F
+where
+    F: Fn(&Progress) + Sync,
+{
+    fn on_progress(&self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+// Observer that ignores everything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressObserver for NoProgress {
+    fn on_progress(&self, _progress: &Progress) {}
+}
+
+// Shared flag for stopping a render early. Clones refer to the same flag, so one can be handed to
+// another thread and cancelled from there while the render checks it between pixels.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,60 @@
+// Small, fast, seedable generator (xoshiro256++ by Blackman and Vigna). Every random decision
+// in the renderer draws from an explicitly passed `Rng`, so a render depends only on its seed.
+// Camera samples get their own generator keyed on (seed, pixel, sample), which keeps the output
+// identical whatever the thread count or the order the pixels are rendered in.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    s: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Expand the seed with SplitMix64, as recommended by the xoshiro authors; this also
+        // guarantees the state is never all zero.
+        let mut x: u64 = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            mix(x)
+        };
+        Self {
+            s: [next(), next(), next(), next()],
+        }
+    }
+
+    // Independent stream for one camera sample of one pixel.
+    pub fn for_sample(seed: u64, pixel: u64, sample: u64) -> Self {
+        Self::new(mix(seed ^ mix(pixel ^ mix(sample))))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result: u64 = (self.s[0].wrapping_add(self.s[3]))
+            .rotate_left(23)
+            .wrapping_add(self.s[0]);
+        let t: u64 = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    // Returns a random real in [0,1).
+    pub fn random_double(&mut self) -> f64 {
+        // The top 53 bits fill the mantissa exactly.
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    // Returns a random real in [min,max).
+    pub fn random_double_range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.random_double()
+    }
+}
+
+// SplitMix64 finalizer: a cheap, well-distributed 64-bit hash.
+pub fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
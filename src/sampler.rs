@@ -0,0 +1,259 @@
+use crate::rng::{mix, Rng};
+
+// Sample pattern used for every random decision of a camera sample: the offset within the pixel,
+// the point on the lens and each bounce's scattering.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SamplerKind {
+    // Independent uniform random numbers.
+    Independent,
+    // Jittered strata, shuffled per pixel and dimension so the dimensions stay uncorrelated, in
+    // rounds of STRATA_SIDE^2 samples.
+    Stratified,
+    // Halton sequence with a random Cranley-Patterson rotation per pixel and dimension.
+    Halton,
+    // Sobol (0, 2)-sequence in pairs of dimensions, with hash-based Owen scrambling and index
+    // shuffling per pixel and dimension pair (Burley 2020, "Practical Hash-based Owen Scrambling").
+    #[default]
+    Sobol,
+}
+
+impl SamplerKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "independent" => Some(Self::Independent),
+            "stratified" => Some(Self::Stratified),
+            "halton" => Some(Self::Halton),
+            "sobol" => Some(Self::Sobol),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Independent => "independent",
+            Self::Stratified => "stratified",
+            Self::Halton => "halton",
+            Self::Sobol => "sobol",
+        }
+    }
+}
+
+// The largest f64 below one, so sample values stay in [0, 1).
+const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
+
+// Side of the stratified sampler's 2D grid; 1D values use as many strata as the grid has cells.
+// The strata don't depend on the sample count, so a sample's values are the same however many
+// samples its pixel ends up with, and resuming with more samples carries on the same rounds.
+const STRATA_SIDE: u32 = 4;
+
+// Bases for the Halton dimensions; later dimensions fall back to independent random numbers.
+const PRIMES: [u64; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+// Hands out the sample values of one camera sample, one dimension at a time. A sampler is keyed
+// on (seed, pixel, sample index) like `Rng::for_sample`, so a sample's values never depend on
+// which thread draws it or in what order.
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    kind: SamplerKind,
+    seed: u64,
+    pixel: u64,
+    sample: u64,
+    dimension: u64,
+    rng: Rng,
+}
+
+impl Sampler {
+    pub fn new(kind: SamplerKind, seed: u64, pixel: u64, sample: u64) -> Self {
+        Self {
+            kind,
+            seed,
+            pixel,
+            sample,
+            dimension: 0,
+            rng: Rng::for_sample(seed, pixel, sample),
+        }
+    }
+
+    // Returns the next sample value in [0,1).
+    pub fn get_1d(&mut self) -> f64 {
+        let dimension: u64 = self.next_dimensions(1);
+        let key: u64 = self.key(dimension);
+        let value: f64 = match self.kind {
+            SamplerKind::Independent => self.rng.random_double(),
+            SamplerKind::Stratified => {
+                let strata: u32 = STRATA_SIDE * STRATA_SIDE;
+                let stratum: u32 = self.stratum(strata, key);
+                (stratum as f64 + self.rng.random_double()) / strata as f64
+            }
+            SamplerKind::Halton => self.halton(dimension, key),
+            SamplerKind::Sobol => sobol_owen(self.sample as u32, key as u32, 0),
+        };
+        value.min(ONE_MINUS_EPSILON)
+    }
+
+    // Returns the next pair of sample values, each in [0,1).
+    pub fn get_2d(&mut self) -> (f64, f64) {
+        let dimension: u64 = self.next_dimensions(2);
+        let key: u64 = self.key(dimension);
+        let (u, v) = match self.kind {
+            SamplerKind::Independent => (self.rng.random_double(), self.rng.random_double()),
+            SamplerKind::Stratified => {
+                let side: u32 = STRATA_SIDE;
+                let stratum: u32 = self.stratum(side * side, key);
+                (
+                    ((stratum % side) as f64 + self.rng.random_double()) / side as f64,
+                    ((stratum / side) as f64 + self.rng.random_double()) / side as f64,
+                )
+            }
+            SamplerKind::Halton => (
+                self.halton(dimension, key),
+                self.halton(dimension + 1, mix(key)),
+            ),
+            SamplerKind::Sobol => {
+                // Both dimensions must share the shuffled index to keep their joint stratification.
+                let index: u32 = nested_uniform_scramble(self.sample as u32, key as u32);
+                (
+                    sobol_scrambled(index, (key >> 32) as u32, 0),
+                    sobol_scrambled(index, mix(key) as u32, 1),
+                )
+            }
+        };
+        (u.min(ONE_MINUS_EPSILON), v.min(ONE_MINUS_EPSILON))
+    }
+
+    fn next_dimensions(&mut self, count: u64) -> u64 {
+        let dimension: u64 = self.dimension;
+        self.dimension += count;
+        dimension
+    }
+
+    // Per pixel and dimension scrambling key.
+    fn key(&self, dimension: u64) -> u64 {
+        mix(self.seed ^ mix(self.pixel ^ mix(dimension ^ 0x5851_f42d_4c95_7f2d)))
+    }
+
+    // Stratum of this sample among `strata`, visited in a per-pixel random order. Samples past
+    // the first `strata` start a new, differently shuffled round.
+    fn stratum(&self, strata: u32, key: u64) -> u32 {
+        let round: u64 = self.sample / strata as u64;
+        let index: u32 = (self.sample % strata as u64) as u32;
+        permute(index, strata, mix(key ^ round) as u32)
+    }
+
+    fn halton(&mut self, dimension: u64, key: u64) -> f64 {
+        match PRIMES.get(dimension as usize) {
+            Some(&base) => {
+                // Cranley-Patterson rotation: shift the whole sequence by a per-pixel offset.
+                let offset: f64 = (key >> 11) as f64 / (1u64 << 53) as f64;
+                (radical_inverse(base, self.sample) + offset).fract()
+            }
+            None => self.rng.random_double(),
+        }
+    }
+}
+
+fn radical_inverse(base: u64, mut index: u64) -> f64 {
+    let inverse_base: f64 = 1.0 / base as f64;
+    let mut factor: f64 = inverse_base;
+    let mut result: f64 = 0.0;
+    while index > 0 {
+        result += (index % base) as f64 * factor;
+        index /= base;
+        factor *= inverse_base;
+    }
+    result
+}
+
+// Element `index` of a pseudo-random permutation of [0, length) selected by `seed`, computed
+// without storing the permutation (Kensler 2013, "Correlated Multi-Jittered Sampling").
+fn permute(mut index: u32, length: u32, seed: u32) -> u32 {
+    let mut w: u32 = length.wrapping_sub(1);
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        index ^= seed;
+        index = index.wrapping_mul(0xe170_893d);
+        index ^= seed >> 16;
+        index ^= (index & w) >> 4;
+        index ^= seed >> 8;
+        index = index.wrapping_mul(0x0929_eb3f);
+        index ^= seed >> 23;
+        index ^= (index & w) >> 1;
+        index = index.wrapping_mul(1 | seed >> 27);
+        index = index.wrapping_mul(0x6935_fa69);
+        index ^= (index & w) >> 11;
+        index = index.wrapping_mul(0x74dc_b303);
+        index ^= (index & w) >> 2;
+        index = index.wrapping_mul(0x9e50_1cc3);
+        index ^= (index & w) >> 2;
+        index = index.wrapping_mul(0xc860_a3df);
+        index &= w;
+        index ^= index >> 5;
+        if index < length {
+            break;
+        }
+    }
+    (index.wrapping_add(seed)) % length
+}
+
+// Direction numbers of the first two Sobol dimensions: the van der Corput sequence and the one
+// from the primitive polynomial x + 1, whose m values follow m_k = 2 m_(k-1) xor m_(k-1).
+const SOBOL_DIRECTIONS: [[u32; 32]; 2] = {
+    let mut directions: [[u32; 32]; 2] = [[0; 32]; 2];
+    let mut m: u32 = 1;
+    let mut bit: usize = 0;
+    while bit < 32 {
+        directions[0][bit] = 1 << (31 - bit);
+        directions[1][bit] = m << (31 - bit);
+        m = (m << 1) ^ m;
+        bit += 1;
+    }
+    directions
+};
+
+fn sobol(index: u32, dimension: usize) -> u32 {
+    let mut result: u32 = 0;
+    let mut bits: u32 = index;
+    let mut bit: usize = 0;
+    while bits != 0 {
+        if bits & 1 == 1 {
+            result ^= SOBOL_DIRECTIONS[dimension][bit];
+        }
+        bits >>= 1;
+        bit += 1;
+    }
+    result
+}
+
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+// Owen scrambling of the bits of x, most significant first.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+fn sobol_scrambled(shuffled_index: u32, seed: u32, dimension: usize) -> f64 {
+    let value: u32 = nested_uniform_scramble(sobol(shuffled_index, dimension), seed);
+    value as f64 / (1u64 << 32) as f64
+}
+
+fn sobol_owen(index: u32, seed: u32, dimension: usize) -> f64 {
+    sobol_scrambled(
+        nested_uniform_scramble(index, seed),
+        mix(seed as u64) as u32,
+        dimension,
+    )
+}
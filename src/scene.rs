@@ -0,0 +1,726 @@
+use std::{
+    collections::BTreeMap,
+    fmt, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::{
+    accumulator::Accumulator,
+    background::{Background, EnvironmentMap},
+    camera::{AdaptiveSampling, Camera, CameraConfig, RenderError, RenderOutput},
+    color::Color,
+    filter::{Filter, FilterKind},
+    framebuffer::Framebuffer,
+    hittables::{
+        bvh::BvhNode, hittable::HittableType, hittable_list::HittableList, sphere::Sphere,
+        triangle::Triangle,
+    },
+    loaders::{obj::load_obj_with_libraries, ply::load_ply, LoadError},
+    materials::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
+        material::Material, metal::Metal,
+    },
+    progress::{CancellationToken, NoProgress, ProgressObserver},
+    ray::Point3,
+    sampler::SamplerKind,
+    sky::Sky,
+    tile::TileConfig,
+    vec3::{cross, Vec3},
+};
+
+// A scene read from a TOML scene description. See the "Scene files" section of the README for
+// the format.
+pub struct Scene {
+    pub world: HittableList,
+    pub camera_config: CameraConfig,
+    pub background: Background,
+    // Files the scene was built from besides its description: meshes, their material libraries
+    // and environment maps.
+    pub assets: Vec<PathBuf>,
+}
+
+impl Scene {
+    pub fn new(world: HittableList, camera_config: CameraConfig) -> Self {
+        Self {
+            world,
+            camera_config,
+            background: Background::default(),
+            assets: Vec::new(),
+        }
+    }
+
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path: &Path = path.as_ref();
+        let source: String = std::fs::read_to_string(path).map_err(|e| SceneError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Self::parse(&source, path)
+    }
+
+    // Parses scene text. `path` is used for error messages and to resolve relative mesh paths.
+    pub fn parse(source: &str, path: &Path) -> Result<Self, SceneError> {
+        let invalid = |span: Option<Range<usize>>, message: String| {
+            let (line, column) = line_column(source, span.map_or(0, |s| s.start));
+            SceneError::Invalid {
+                path: path.to_path_buf(),
+                line,
+                column,
+                message,
+            }
+        };
+
+        let file: SceneFile =
+            toml::from_str(source).map_err(|e| invalid(e.span(), e.message().to_string()))?;
+
+        let mut materials: BTreeMap<&str, Material> = BTreeMap::new();
+        for (name, spec) in &file.materials {
+            let material: Material = spec.get_ref().build().map_err(|msg| {
+                invalid(Some(spec.span()), format!("materials.{}: {}", name, msg))
+            })?;
+            materials.insert(name.as_str(), material);
+        }
+        let lookup = |field: &str, name: &Spanned<String>| {
+            materials
+                .get(name.get_ref().as_str())
+                .cloned()
+                .ok_or_else(|| {
+                    invalid(
+                        Some(name.span()),
+                        format!("{}: unknown material '{}'", field, name.get_ref()),
+                    )
+                })
+        };
+
+        let base_dir: &Path = path.parent().unwrap_or(Path::new(""));
+        let mut world: HittableList = HittableList::default();
+        let mut assets: Vec<PathBuf> = Vec::new();
+        for (idx, object) in file.objects.iter().enumerate() {
+            let spec: &ObjectSpec = object.get_ref();
+            let field = |name: &str| format!("objects[{}].{}", idx, name);
+            let missing = |name: &str| {
+                invalid(
+                    Some(object.span()),
+                    format!(
+                        "{}: required for {} objects",
+                        field(name),
+                        spec.kind.get_ref()
+                    ),
+                )
+            };
+
+            let hittable: HittableType = match spec.kind.get_ref().as_str() {
+                "sphere" => {
+                    let center: [f64; 3] = spec.center.ok_or_else(|| missing("center"))?;
+                    let radius: &Spanned<f64> =
+                        spec.radius.as_ref().ok_or_else(|| missing("radius"))?;
+                    if *radius.get_ref() <= 0.0 {
+                        return Err(invalid(
+                            Some(radius.span()),
+                            format!("{}: must be positive", field("radius")),
+                        ));
+                    }
+                    let material: &Spanned<String> =
+                        spec.material.as_ref().ok_or_else(|| missing("material"))?;
+                    HittableType::Sphere(Arc::new(Sphere::new(
+                        &to_vec3(center),
+                        *radius.get_ref(),
+                        lookup(&field("material"), material)?,
+                    )))
+                }
+                "triangle" => {
+                    let vertices: [Point3; 3] = spec
+                        .vertices
+                        .ok_or_else(|| missing("vertices"))?
+                        .map(to_vec3);
+                    let material: &Spanned<String> =
+                        spec.material.as_ref().ok_or_else(|| missing("material"))?;
+                    let material: Material = lookup(&field("material"), material)?;
+                    HittableType::Triangle(Arc::new(match spec.normals {
+                        Some(normals) => {
+                            Triangle::with_normals(vertices, normals.map(to_vec3), material)
+                        }
+                        None => Triangle::new(&vertices[0], &vertices[1], &vertices[2], material),
+                    }))
+                }
+                "mesh" => {
+                    let mesh_path: &Spanned<String> =
+                        spec.path.as_ref().ok_or_else(|| missing("path"))?;
+                    let material: Material = match &spec.material {
+                        Some(name) => lookup(&field("material"), name)?,
+                        None => Material::Lambertian(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+                    };
+                    let file_path: PathBuf = base_dir.join(mesh_path.get_ref());
+                    let extension: String = file_path
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .unwrap_or_default()
+                        .to_ascii_lowercase();
+                    let mesh = match extension.as_str() {
+                        "obj" => load_obj_with_libraries(&file_path, material).map(
+                            |(mesh, libraries)| {
+                                assets.extend(libraries);
+                                mesh
+                            },
+                        ),
+                        "ply" => load_ply(&file_path, material),
+                        _ => {
+                            return Err(invalid(
+                                Some(mesh_path.span()),
+                                format!("{}: expected an .obj or .ply file", field("path")),
+                            ))
+                        }
+                    }
+                    .map_err(SceneError::Mesh)?;
+                    assets.push(file_path);
+                    HittableType::Mesh(Arc::new(mesh))
+                }
+                other => {
+                    return Err(invalid(
+                        Some(spec.kind.span()),
+                        format!(
+                            "{}: unknown object type '{}' (expected sphere, triangle or mesh)",
+                            field("type"),
+                            other
+                        ),
+                    ))
+                }
+            };
+            world.add(hittable);
+        }
+
+        let background: Background = match &file.background {
+            Some(spec) => spec
+                .get_ref()
+                .build(base_dir, &mut assets)
+                .map_err(|msg| invalid(Some(spec.span()), format!("background.{}", msg)))?,
+            None => Background::default(),
+        };
+
+        let default_render: RenderSection = RenderSection::default();
+        let render: &RenderSection = file
+            .render
+            .as_ref()
+            .map_or(&default_render, |r| r.get_ref());
+        if let Err(msg) = render.validate() {
+            let span: Option<Range<usize>> = file.render.as_ref().map(|r| r.span());
+            return Err(invalid(span, format!("render.{}", msg)));
+        }
+
+        let camera: &CameraSection = file.camera.get_ref();
+        if let Err(msg) = camera.validate() {
+            return Err(invalid(Some(file.camera.span()), format!("camera.{}", msg)));
+        }
+        let camera_config: CameraConfig = CameraConfig {
+            aspect_ratio: render.aspect_ratio,
+            image_width: render.image_width as f64,
+            samples_per_pixel: render.samples_per_pixel as f64,
+            max_depth: render.max_depth,
+            vfov: camera.vfov,
+            look_from: to_vec3(camera.look_from),
+            look_at: to_vec3(camera.look_at),
+            v_up: to_vec3(camera.v_up),
+            defocus_angle: camera.defocus_angle,
+            focus_dist: camera.focus_dist,
+            seed: render.seed,
+            tiles: TileConfig::default(),
+            adaptive: render.adaptive_threshold.map(|threshold| AdaptiveSampling {
+                threshold,
+                min_samples: render.min_samples_per_pixel,
+            }),
+            // Checked by `RenderSection::validate`.
+            sampler: SamplerKind::from_name(&render.sampler).unwrap_or_default(),
+            filter: {
+                let kind: FilterKind = FilterKind::from_name(&render.filter).unwrap_or_default();
+                Filter::with_radius(kind, render.filter_radius.unwrap_or(kind.default_radius()))
+            },
+        };
+
+        Ok(Self {
+            world,
+            camera_config,
+            background,
+            assets,
+        })
+    }
+
+    pub fn camera(&self) -> Camera {
+        Camera::new(self.camera_config).with_background(self.background.clone())
+    }
+
+    // Builds a BVH over the world and renders it through the scene's camera.
+    pub fn render(self) -> Framebuffer {
+        self.render_with(&NoProgress, &CancellationToken::new())
+            .image
+    }
+
+    // Like `render`, with progress reporting and cancellation as in `Camera::render_with`.
+    pub fn render_with(
+        self,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+    ) -> RenderOutput {
+        let camera: Camera = self.camera();
+        let world: HittableType = HittableType::Bvh(Arc::new(BvhNode::new(self.world)));
+        camera.render_with(&world, observer, cancel)
+    }
+
+    // Renders in passes as `Camera::render_progressive` does.
+    pub fn render_progressive(
+        self,
+        samples_per_pass: u32,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+        on_pass: &mut dyn FnMut(u32, &Accumulator),
+    ) -> RenderOutput {
+        let camera: Camera = self.camera();
+        let world: HittableType = HittableType::Bvh(Arc::new(BvhNode::new(self.world)));
+        camera.render_progressive(&world, samples_per_pass, observer, cancel, on_pass)
+    }
+
+    // Continues from accumulated samples as `Camera::resume` does.
+    pub fn resume(
+        self,
+        accumulator: Accumulator,
+        samples_per_pass: u32,
+        observer: &dyn ProgressObserver,
+        cancel: &CancellationToken,
+        on_pass: &mut dyn FnMut(u32, &Accumulator),
+    ) -> Result<RenderOutput, RenderError> {
+        let camera: Camera = self.camera();
+        let world: HittableType = HittableType::Bvh(Arc::new(BvhNode::new(self.world)));
+        camera.resume(
+            &world,
+            accumulator,
+            samples_per_pass,
+            observer,
+            cancel,
+            on_pass,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Mesh(LoadError),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Self::Invalid {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+            Self::Mesh(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Invalid { .. } => None,
+            Self::Mesh(e) => Some(e),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    camera: Spanned<CameraSection>,
+    render: Option<Spanned<RenderSection>>,
+    background: Option<Spanned<BackgroundSpec>>,
+    #[serde(default)]
+    materials: BTreeMap<String, Spanned<MaterialSpec>>,
+    #[serde(default)]
+    objects: Vec<Spanned<ObjectSpec>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraSection {
+    look_from: [f64; 3],
+    look_at: [f64; 3],
+    #[serde(default = "default_v_up")]
+    v_up: [f64; 3],
+    #[serde(default = "default_vfov")]
+    vfov: f64,
+    #[serde(default)]
+    defocus_angle: f64,
+    #[serde(default = "default_focus_dist")]
+    focus_dist: f64,
+}
+
+impl CameraSection {
+    fn validate(&self) -> Result<(), String> {
+        let (look_from, look_at, v_up) = (
+            to_vec3(self.look_from),
+            to_vec3(self.look_at),
+            to_vec3(self.v_up),
+        );
+        let view: Vec3 = look_at - look_from;
+        match self {
+            c if !c.look_from.iter().all(|x| x.is_finite()) => {
+                Err("look_from: must be finite".to_string())
+            }
+            c if !c.look_at.iter().all(|x| x.is_finite()) => {
+                Err("look_at: must be finite".to_string())
+            }
+            c if !c.v_up.iter().all(|x| x.is_finite()) => Err("v_up: must be finite".to_string()),
+            _ if view.near_zero() => Err("look_at: must differ from look_from".to_string()),
+            // The camera's right and up axes are built from v_up x view.
+            _ if cross(v_up, view).length() <= 1e-9 * v_up.length() * view.length() => {
+                Err("v_up: must not be zero or parallel to the view direction".to_string())
+            }
+            c if !(c.vfov > 0.0 && c.vfov < 180.0) => {
+                Err("vfov: must be between 0 and 180 degrees".to_string())
+            }
+            c if !(c.defocus_angle >= 0.0 && c.defocus_angle < 180.0) => {
+                Err("defocus_angle: must be at least 0 and below 180 degrees".to_string())
+            }
+            c if !is_positive(c.focus_dist) => Err("focus_dist: must be positive".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RenderSection {
+    image_width: u32,
+    aspect_ratio: f64,
+    samples_per_pixel: u32,
+    max_depth: i16,
+    seed: u64,
+    adaptive_threshold: Option<f64>,
+    min_samples_per_pixel: u32,
+    sampler: String,
+    filter: String,
+    filter_radius: Option<f64>,
+}
+
+impl RenderSection {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            r if r.image_width == 0 => Err("image_width: must be at least 1".to_string()),
+            r if !is_positive(r.aspect_ratio) => Err("aspect_ratio: must be positive".to_string()),
+            r if r.samples_per_pixel == 0 => {
+                Err("samples_per_pixel: must be at least 1".to_string())
+            }
+            r if r.max_depth < 1 => Err("max_depth: must be at least 1".to_string()),
+            r if r.adaptive_threshold.is_some_and(|t| !is_positive(t)) => {
+                Err("adaptive_threshold: must be positive".to_string())
+            }
+            r if r.adaptive_threshold.is_some() && r.min_samples_per_pixel < 2 => {
+                Err("min_samples_per_pixel: must be at least 2".to_string())
+            }
+            r if SamplerKind::from_name(&r.sampler).is_none() => Err(format!(
+                "sampler: unknown sampler '{}' (expected independent, stratified, halton or sobol)",
+                r.sampler
+            )),
+            r if FilterKind::from_name(&r.filter).is_none() => Err(format!(
+                "filter: unknown filter '{}' (expected box, tent, gaussian, mitchell or lanczos)",
+                r.filter
+            )),
+            r if r.filter_radius.is_some_and(|radius| !is_positive(radius)) => {
+                Err("filter_radius: must be positive".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for RenderSection {
+    fn default() -> Self {
+        Self {
+            image_width: 400,
+            aspect_ratio: 16.0 / 9.0,
+            samples_per_pixel: 100,
+            max_depth: 50,
+            seed: 0,
+            adaptive_threshold: None,
+            min_samples_per_pixel: AdaptiveSampling::DEFAULT_MIN_SAMPLES,
+            sampler: "sobol".to_string(),
+            filter: "box".to_string(),
+            filter_radius: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaterialSpec {
+    #[serde(rename = "type")]
+    kind: String,
+    albedo: Option<[f64; 3]>,
+    fuzz: Option<f64>,
+    refraction_index: Option<f64>,
+    emit: Option<[f64; 3]>,
+}
+
+impl MaterialSpec {
+    fn build(&self) -> Result<Material, String> {
+        let albedo = || {
+            self.albedo
+                .map(to_vec3)
+                .ok_or_else(|| format!("albedo is required for {} materials", self.kind))
+        };
+        match self.kind.as_str() {
+            "lambertian" => Ok(Material::Lambertian(Lambertian::new(albedo()?))),
+            "metal" => Ok(Material::Metal(Metal::new(
+                albedo()?,
+                self.fuzz.unwrap_or(0.0).clamp(0.0, 1.0),
+            ))),
+            "dielectric" => Ok(Material::Dielectric(Dielectric::new(
+                self.refraction_index
+                    .ok_or("refraction_index is required for dielectric materials")?,
+            ))),
+            "diffuse_light" => Ok(Material::DiffuseLight(DiffuseLight::new(
+                self.emit
+                    .map(to_vec3)
+                    .ok_or("emit is required for diffuse_light materials")?,
+            ))),
+            other => Err(format!(
+                "unknown material type '{}' (expected lambertian, metal, dielectric or diffuse_light)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackgroundSpec {
+    #[serde(rename = "type")]
+    kind: String,
+    color: Option<[f64; 3]>,
+    bottom: Option<[f64; 3]>,
+    top: Option<[f64; 3]>,
+    path: Option<String>,
+    rotation: Option<f64>,
+    intensity: Option<f64>,
+    sun_elevation: Option<f64>,
+    sun_azimuth: Option<f64>,
+    turbidity: Option<f64>,
+}
+
+impl BackgroundSpec {
+    // Adds the files it reads to `assets`.
+    fn build(&self, base_dir: &Path, assets: &mut Vec<PathBuf>) -> Result<Background, String> {
+        let intensity: f64 = self.intensity.unwrap_or(1.0);
+        if intensity < 0.0 {
+            return Err("intensity: must not be negative".to_string());
+        }
+        match self.kind.as_str() {
+            "solid" => Ok(Background::Solid(
+                self.color
+                    .map(to_vec3)
+                    .ok_or("color: required for solid backgrounds")?,
+            )),
+            "gradient" => Ok(Background::Gradient {
+                bottom: self.bottom.map_or(Background::SKY_BOTTOM, to_vec3),
+                top: self.top.map_or(Background::SKY_TOP, to_vec3),
+            }),
+            "environment" => {
+                let path: &String = self
+                    .path
+                    .as_ref()
+                    .ok_or("path: required for environment backgrounds")?;
+                let file_path: PathBuf = base_dir.join(path);
+                let map: EnvironmentMap =
+                    EnvironmentMap::load(&file_path, self.rotation.unwrap_or(0.0), intensity)
+                        .map_err(|e| format!("path: {}", e))?;
+                assets.push(file_path);
+                Ok(Background::Environment(Arc::new(map)))
+            }
+            "sky" => {
+                // The model is fitted for a sun above the horizon and turbidities from 2 to 10.
+                let sun_elevation: f64 = self.sun_elevation.unwrap_or(45.0);
+                if !(0.0..=90.0).contains(&sun_elevation) {
+                    return Err("sun_elevation: must be between 0 and 90 degrees".to_string());
+                }
+                let turbidity: f64 = self.turbidity.unwrap_or(3.0);
+                if !(2.0..=10.0).contains(&turbidity) {
+                    return Err("turbidity: must be between 2 and 10".to_string());
+                }
+                Ok(Background::Sky(Sky::new(
+                    sun_elevation,
+                    self.sun_azimuth.unwrap_or(0.0),
+                    turbidity,
+                    intensity,
+                )))
+            }
+            other => Err(format!(
+                "type: unknown background type '{}' (expected solid, gradient, environment or sky)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectSpec {
+    #[serde(rename = "type")]
+    kind: Spanned<String>,
+    center: Option<[f64; 3]>,
+    radius: Option<Spanned<f64>>,
+    vertices: Option<[[f64; 3]; 3]>,
+    normals: Option<[[f64; 3]; 3]>,
+    path: Option<Spanned<String>>,
+    material: Option<Spanned<String>>,
+}
+
+fn default_v_up() -> [f64; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_vfov() -> f64 {
+    90.0
+}
+
+fn default_focus_dist() -> f64 {
+    10.0
+}
+
+fn to_vec3(e: [f64; 3]) -> Vec3 {
+    Vec3 { e }
+}
+
+// Checks with `<= 0.0` alone would let NaN through, and infinities are no more usable.
+fn is_positive(x: f64) -> bool {
+    x.is_finite() && x > 0.0
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    // 1-based line and column of a byte offset into `source`.
+    let before: &str = &source[..offset.min(source.len())];
+    let line: usize = before.matches('\n').count() + 1;
+    let column: usize = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_position(source: &str) -> (usize, usize, String) {
+        match Scene::parse(source, Path::new("test.toml")) {
+            Err(SceneError::Invalid {
+                line,
+                column,
+                message,
+                ..
+            }) => (line, column, message),
+            Err(e) => panic!("expected an invalid scene, got {}", e),
+            Ok(_) => panic!("expected an invalid scene"),
+        }
+    }
+
+    const CAMERA: &str = "[camera]\nlook_from = [0.0, 0.0, 1.0]\nlook_at = [0.0, 0.0, 0.0]\n";
+
+    #[test]
+    fn errors_point_at_line_and_column() {
+        let cases: [(String, usize, usize); 7] = [
+            // Syntax errors.
+            ("[camera\n".to_string(), 1, 8),
+            (format!("{}vfov = \n", CAMERA), 4, 8),
+            // Missing and unknown keys.
+            ("[render]\nimage_width = 10\n".to_string(), 1, 1),
+            (format!("{}fov = 40.0\n", CAMERA), 4, 1),
+            // Values the format accepts but the renderer does not.
+            (
+                format!(
+                    "{}\n[[objects]]\ntype = \"sphere\"\ncenter = [0.0, 0.0, 0.0]\nradius = 1.0\nmaterial = \"missing\"\n",
+                    CAMERA
+                ),
+                9,
+                12,
+            ),
+            (format!("{}\n[render]\nmax_depth = 0\n", CAMERA), 5, 1),
+            (
+                format!("{}\n[render]\nsampler = \"random\"\n", CAMERA),
+                5,
+                1,
+            ),
+        ];
+        for (source, expected_line, expected_column) in cases {
+            let (line, column, message) = error_position(&source);
+            assert_eq!(
+                (line, column),
+                (expected_line, expected_column),
+                "{}\n{}",
+                message,
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unusable_numbers_and_a_degenerate_camera() {
+        let cases: [(String, &str); 8] = [
+            (
+                format!("{}\n[render]\naspect_ratio = nan\n", CAMERA),
+                "aspect_ratio",
+            ),
+            (
+                format!("{}\n[render]\naspect_ratio = inf\n", CAMERA),
+                "aspect_ratio",
+            ),
+            (
+                format!("{}\n[render]\nfilter_radius = nan\n", CAMERA),
+                "filter_radius",
+            ),
+            (format!("{}focus_dist = 0.0\n", CAMERA), "focus_dist"),
+            (format!("{}vfov = nan\n", CAMERA), "vfov"),
+            (CAMERA.replace("1.0]", "nan]"), "look_from"),
+            (CAMERA.replace("1.0]", "0.0]"), "look_at"),
+            (format!("{}v_up = [0.0, 0.0, 2.0]\n", CAMERA), "v_up"),
+        ];
+        for (source, field) in cases {
+            let (line, column, message) = error_position(&source);
+            let expected: (usize, usize) = match source.contains("[render]") {
+                true => (5, 1),
+                false => (1, 1),
+            };
+            assert_eq!((line, column), expected, "{}", message);
+            assert!(message.contains(field), "{}: {}", field, message);
+        }
+        assert!(Scene::parse(CAMERA, Path::new("test.toml")).is_ok());
+    }
+
+    #[test]
+    fn adaptive_minimum_only_checked_when_adaptive() {
+        let source: String = format!("{}[render]\nmin_samples_per_pixel = 1\n", CAMERA);
+        assert!(Scene::parse(&source, Path::new("test.toml")).is_ok());
+        let (line, _, message) = error_position(&format!("{}adaptive_threshold = 0.01\n", source));
+        assert_eq!(line, 4);
+        assert!(message.contains("min_samples_per_pixel"), "{}", message);
+    }
+}
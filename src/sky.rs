@@ -0,0 +1,181 @@
+use std::f64::consts::PI;
+
+use crate::{
+    color::Color,
+    vec3::{dot, sample_cone, unit_vector, Vec3},
+};
+
+// Angular radius of the sun's disk as seen from the earth.
+const SUN_ANGULAR_RADIUS: f64 = 0.004654; // In radians, 0.2667 degrees
+
+// The model works in kcd/m^2; this brings a surface lit by a high sun to around 1.
+const LUMINANCE_SCALE: f64 = 0.05;
+
+// Luminance of the sun's disk above the atmosphere, in kcd/m^2.
+const SUN_LUMINANCE: f64 = 1.6e6;
+
+// Clear daylight sky after Preetham, Shirley and Smits 1999, "A Practical Analytic Model for
+// Daylight": Perez distributions of luminance and chromaticity fitted to the sun's position and
+// the atmospheric turbidity (2 for a very clear sky, around 3 for a typical clear day, 6 and more
+// for haze). The sun is a disk of the real angular size whose color comes from Rayleigh and
+// aerosol extinction along its path through the atmosphere. The sky below the horizon repeats the
+// horizon, since the model does not cover it.
+#[derive(Clone, Debug)]
+pub struct Sky {
+    sun_direction: Vec3,
+    intensity: f64,
+    // Perez coefficients A to E for luminance Y and chromaticities x and y.
+    perez: [[f64; 5]; 3],
+    // Zenith luminance and chromaticity divided by the Perez function at the zenith, so that
+    // multiplying by the Perez function gives the value in any direction.
+    zenith: [f64; 3],
+    sun_radiance: Color,
+    cos_sun_radius: f64,
+}
+
+impl Sky {
+    // The sun's elevation is in degrees above the horizon, its azimuth in degrees from -z towards
+    // +x, so that an azimuth of 0 puts it straight ahead of a camera looking down -z.
+    pub fn new(sun_elevation: f64, sun_azimuth: f64, turbidity: f64, intensity: f64) -> Self {
+        let (elevation, azimuth) = (sun_elevation.to_radians(), sun_azimuth.to_radians());
+        let sun_direction: Vec3 = Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            -elevation.cos() * azimuth.cos(),
+        );
+        let theta_s: f64 = PI / 2.0 - elevation;
+        let t: f64 = turbidity;
+
+        let perez: [[f64; 5]; 3] = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let chi: f64 = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_luminance: f64 = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let powers: [f64; 4] = [theta_s.powi(3), theta_s.powi(2), theta_s, 1.0];
+            let row = |r: [f64; 4]| (0..4).map(|i| r[i] * powers[i]).sum::<f64>();
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+        let zenith_x: f64 = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_y: f64 = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        let zenith: [f64; 3] = [zenith_luminance, zenith_x, zenith_y];
+
+        Self {
+            sun_direction,
+            intensity,
+            zenith: [0, 1, 2].map(|i| zenith[i] / perez_function(perez[i], 0.0, theta_s)),
+            perez,
+            sun_radiance: sun_radiance(theta_s, t),
+            cos_sun_radius: SUN_ANGULAR_RADIUS.cos(),
+        }
+    }
+
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    pub fn radiance(&self, direction: Vec3) -> Color {
+        let d: Vec3 = unit_vector(direction);
+        let cos_gamma: f64 = dot(d, self.sun_direction).clamp(-1.0, 1.0);
+        let sun: Color = match cos_gamma >= self.cos_sun_radius {
+            true => self.sun_radiance,
+            false => Color::default(),
+        };
+        // Directions at or below the horizon see the horizon.
+        let theta: f64 = d.y().clamp(0.0, 1.0).acos().min(PI / 2.0 - 1e-3);
+        let gamma: f64 = cos_gamma.acos();
+        let [luminance, x, y] =
+            [0, 1, 2].map(|i| self.zenith[i] * perez_function(self.perez[i], theta, gamma));
+        self.intensity * (LUMINANCE_SCALE * xyy_to_rgb(x, y, luminance.max(0.0)) + sun)
+    }
+
+    // Picks a direction within the sun's disk, uniformly in solid angle, returning it with its
+    // probability density. The disk is what makes sunlit scenes noisy to find by chance.
+    pub fn sample_sun(&self, u: (f64, f64)) -> Option<(Vec3, f64)> {
+        let direction: Vec3 = sample_cone(self.sun_direction, self.cos_sun_radius, u);
+        Some((direction, self.sun_pdf()))
+    }
+
+    // Density per unit solid angle with which `sample_sun` picks `direction`.
+    pub fn pdf(&self, direction: Vec3) -> f64 {
+        match dot(unit_vector(direction), self.sun_direction) >= self.cos_sun_radius {
+            true => self.sun_pdf(),
+            false => 0.0,
+        }
+    }
+
+    fn sun_pdf(&self) -> f64 {
+        1.0 / (2.0 * PI * (1.0 - self.cos_sun_radius))
+    }
+}
+
+fn perez_function([a, b, c, d, e]: [f64; 5], theta: f64, gamma: f64) -> f64 {
+    (1.0 + a * (b / theta.cos()).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos().powi(2))
+}
+
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Color {
+    if y <= 0.0 {
+        return Color::default();
+    }
+    let big_x: f64 = x / y * luminance;
+    let big_z: f64 = (1.0 - x - y) / y * luminance;
+    // CIE XYZ to linear sRGB (D65).
+    Color::new(
+        3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z,
+        -0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z,
+        0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z,
+    )
+}
+
+// Radiance of the sun's disk after extinction through the atmosphere at zenith angle `theta_s`,
+// evaluated at representative wavelengths for red, green and blue.
+fn sun_radiance(theta_s: f64, turbidity: f64) -> Color {
+    // Relative optical air mass (Kasten and Young), finite down to the horizon.
+    let mass: f64 =
+        1.0 / (theta_s.cos() + 0.50572 * (96.07995 - theta_s.to_degrees()).powf(-1.6364));
+    // Angstrom turbidity coefficient and wavelength exponent of the aerosols.
+    let beta: f64 = 0.04608 * turbidity - 0.04586;
+    let alpha: f64 = 1.3;
+    let transmittance = |lambda: f64| {
+        // `lambda` in micrometres.
+        let rayleigh: f64 = (-0.008735 * lambda.powf(-4.08) * mass).exp();
+        let aerosol: f64 = (-beta * lambda.powf(-alpha) * mass).exp();
+        rayleigh * aerosol
+    };
+    LUMINANCE_SCALE
+        * SUN_LUMINANCE
+        * Color::new(
+            transmittance(0.65),
+            transmittance(0.55),
+            transmittance(0.45),
+        )
+}
@@ -0,0 +1,124 @@
+// Rectangular block of pixels rendered as one unit of work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tile {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Tile {
+    pub fn pixel_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    // The tile grown by `margin` pixels on every side, clipped to a width x height image.
+    pub fn expand(&self, margin: usize, width: usize, height: usize) -> Tile {
+        let (x, y) = (self.x.saturating_sub(margin), self.y.saturating_sub(margin));
+        Tile {
+            x,
+            y,
+            width: (self.x + self.width + margin).min(width) - x,
+            height: (self.y + self.height + margin).min(height) - y,
+        }
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+// Order in which tiles are handed out to the render threads. It never changes the image, only
+// how it fills in and how well neighbouring work shares caches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TileOrder {
+    // Left to right, top to bottom.
+    Scanline,
+    // Outwards from the centre of the image, where the subject usually is.
+    #[default]
+    Spiral,
+    // Along a Hilbert curve, so consecutive tiles are always adjacent.
+    Hilbert,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileConfig {
+    pub size: usize,
+    pub order: TileOrder,
+}
+
+impl Default for TileConfig {
+    fn default() -> Self {
+        Self {
+            size: 32,
+            order: TileOrder::default(),
+        }
+    }
+}
+
+impl TileConfig {
+    // Splits a width x height image into tiles of at most size x size pixels, in render order.
+    pub fn tiles(&self, width: usize, height: usize) -> Vec<Tile> {
+        let size: usize = self.size.max(1);
+        let columns: usize = width.div_ceil(size);
+        let rows: usize = height.div_ceil(size);
+
+        let mut grid: Vec<(usize, usize)> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .collect();
+        match self.order {
+            TileOrder::Scanline => {}
+            TileOrder::Spiral => {
+                // Ring by ring outwards from the centre tile, sweeping each ring by angle.
+                let cx: f64 = (columns as f64 - 1.0) / 2.0;
+                let cy: f64 = (rows as f64 - 1.0) / 2.0;
+                let key = |&(column, row): &(usize, usize)| {
+                    let dx: f64 = column as f64 - cx;
+                    let dy: f64 = row as f64 - cy;
+                    let ring: f64 = dx.abs().max(dy.abs()).round();
+                    let angle: f64 = (-dx).atan2(-dy);
+                    (ring, angle)
+                };
+                grid.sort_by(|a, b| key(a).partial_cmp(&key(b)).unwrap());
+            }
+            TileOrder::Hilbert => {
+                let side: usize = columns.max(rows).next_power_of_two();
+                grid.sort_by_key(|&(column, row)| hilbert_index(side, column, row));
+            }
+        }
+
+        grid.into_iter()
+            .map(|(column, row)| {
+                let x: usize = column * size;
+                let y: usize = row * size;
+                Tile {
+                    x,
+                    y,
+                    width: size.min(width - x),
+                    height: size.min(height - y),
+                }
+            })
+            .collect()
+    }
+}
+
+// Distance of (x, y) along the Hilbert curve filling a side x side grid, side a power of two.
+fn hilbert_index(side: usize, mut x: usize, mut y: usize) -> usize {
+    let mut d: usize = 0;
+    let mut s: usize = side / 2;
+    while s > 0 {
+        let rx: usize = usize::from(x & s > 0);
+        let ry: usize = usize::from(y & s > 0);
+        d += s * s * ((3 * rx) ^ ry);
+        // Rotate the quadrant so the curve stays continuous.
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
@@ -0,0 +1,84 @@
+use crate::color::{luminance, Color};
+
+// Operator compressing scene-referred linear radiance into the displayable [0, 1] range.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ToneMapper {
+    // Clip every channel to [0, 1].
+    #[default]
+    Clamp,
+    // Extended Reinhard on luminance; `white` is the luminance that maps to pure white.
+    Reinhard {
+        white: f64,
+    },
+    // Narkowicz's curve fit of the ACES filmic reference rendering transform.
+    Aces,
+    // John Hable's filmic curve from Uncharted 2.
+    Hable,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ToneMapping {
+    pub operator: ToneMapper,
+    pub exposure: f64, // In stops (EV); every +1 doubles the radiance before mapping
+}
+
+impl ToneMapping {
+    pub fn new(operator: ToneMapper, exposure: f64) -> Self {
+        Self { operator, exposure }
+    }
+
+    // Maps linear radiance to linear display values in [0, 1], ready for the sRGB transfer.
+    pub fn apply(&self, radiance: Color) -> Color {
+        let exposed: Color = 2f64.powf(self.exposure) * clamp_negative(radiance);
+        let mapped: Color = match self.operator {
+            ToneMapper::Clamp => exposed,
+            ToneMapper::Reinhard { white } => reinhard_extended(exposed, white),
+            ToneMapper::Aces => Color {
+                e: exposed.e.map(aces_filmic),
+            },
+            ToneMapper::Hable => Color {
+                e: exposed.e.map(hable_filmic),
+            },
+        };
+        Color {
+            e: mapped.e.map(|c| c.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+fn clamp_negative(c: Color) -> Color {
+    // Negative or NaN radiance has no meaning on a display.
+    Color {
+        e: c.e.map(|x| match x > 0.0 {
+            true => x,
+            false => 0.0,
+        }),
+    }
+}
+
+fn reinhard_extended(c: Color, white: f64) -> Color {
+    let l_in: f64 = luminance(c);
+    if l_in <= 0.0 {
+        return Color::default();
+    }
+    let l_out: f64 = l_in * (1.0 + l_in / (white * white)) / (1.0 + l_in);
+    (l_out / l_in) * c
+}
+
+fn aces_filmic(x: f64) -> f64 {
+    // The fit expects radiance pre-scaled by 0.6 to match the reference exposure.
+    let x: f64 = 0.6 * x;
+    let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+    (x * (a * x + b)) / (x * (c * x + d) + e)
+}
+
+fn hable_filmic(x: f64) -> f64 {
+    fn curve(x: f64) -> f64 {
+        let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+        ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+    }
+    // Linear white point and exposure bias from the original presentation.
+    const WHITE: f64 = 11.2;
+    const EXPOSURE_BIAS: f64 = 2.0;
+    curve(EXPOSURE_BIAS * x) / curve(WHITE)
+}
@@ -1,4 +1,6 @@
-use crate::util::{random_double, random_double_range};
+use std::f64::consts::PI;
+
+use crate::{rng::Rng, sampler::Sampler};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Vec3 {
@@ -36,15 +38,19 @@ impl Vec3 {
         self.e[0].powi(2) + self.e[1].powi(2) + self.e[2].powi(2)
     }
 
-    pub fn random() -> Self {
-        Self::new(random_double(), random_double(), random_double())
+    pub fn random(rng: &mut Rng) -> Self {
+        Self::new(
+            rng.random_double(),
+            rng.random_double(),
+            rng.random_double(),
+        )
     }
 
-    pub fn random_range(min: f64, max: f64) -> Self {
+    pub fn random_range(rng: &mut Rng, min: f64, max: f64) -> Self {
         Self::new(
-            random_double_range(min, max),
-            random_double_range(min, max),
-            random_double_range(min, max),
+            rng.random_double_range(min, max),
+            rng.random_double_range(min, max),
+            rng.random_double_range(min, max),
         )
     }
 
@@ -151,31 +157,33 @@ pub fn unit_vector(v: Vec3) -> Vec3 {
     v / v.length()
 }
 
-pub fn random_unit_vector() -> Vec3 {
-    loop {
-        let p: Vec3 = Vec3::random();
-        let lens_q: f64 = p.length_squared();
-        if 1.0e-160 < lens_q && lens_q <= 1.0 {
-            return p / lens_q.sqrt();
-        }
-    }
+pub fn random_unit_vector(sampler: &mut Sampler) -> Vec3 {
+    // Uniform on the sphere: z is uniform in [-1, 1] by Archimedes' hat-box theorem. A direct
+    // mapping rather than rejection, so stratified sample points stay stratified.
+    let (u, v) = sampler.get_2d();
+    let z: f64 = 1.0 - 2.0 * u;
+    let r: f64 = (1.0 - z * z).max(0.0).sqrt();
+    let phi: f64 = 2.0 * PI * v;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
 }
 
-pub fn random_in_unit_disk() -> Vec3 {
-    loop {
-        let p: Vec3 = Vec3::new(
-            random_double_range(-1.0, 1.0),
-            random_double_range(-1.0, 1.0),
-            0.0,
-        );
-        if p.length_squared() < 1.0 {
-            return p;
-        }
+pub fn random_in_unit_disk(sampler: &mut Sampler) -> Vec3 {
+    // Shirley and Chiu's concentric mapping of the square onto the disk, which keeps strata
+    // compact.
+    let (u, v) = sampler.get_2d();
+    let (a, b) = (2.0 * u - 1.0, 2.0 * v - 1.0);
+    if a == 0.0 && b == 0.0 {
+        return Vec3::default();
     }
+    let (r, phi) = match a.abs() > b.abs() {
+        true => (a, PI / 4.0 * (b / a)),
+        false => (b, PI / 2.0 - PI / 4.0 * (a / b)),
+    };
+    Vec3::new(r * phi.cos(), r * phi.sin(), 0.0)
 }
 
-pub fn random_on_hemisphere(normal: Vec3) -> Vec3 {
-    let on_unit_sphere: Vec3 = random_unit_vector();
+pub fn random_on_hemisphere(sampler: &mut Sampler, normal: Vec3) -> Vec3 {
+    let on_unit_sphere: Vec3 = random_unit_vector(sampler);
 
     // in the same hemisphere as the normal
     match dot(on_unit_sphere, normal) > 0.0 {
@@ -184,6 +192,22 @@ pub fn random_on_hemisphere(normal: Vec3) -> Vec3 {
     }
 }
 
+// Direction uniformly distributed in solid angle within the cone of directions at most
+// acos(cos_max) from the unit vector `axis`, for a pair of uniform samples.
+pub fn sample_cone(axis: Vec3, cos_max: f64, (u, v): (f64, f64)) -> Vec3 {
+    let cos_theta: f64 = 1.0 - u * (1.0 - cos_max);
+    let sin_theta: f64 = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi: f64 = 2.0 * PI * v;
+    // Orthonormal frame around the axis.
+    let a: Vec3 = match axis.x().abs() > 0.9 {
+        true => Vec3::new(0.0, 1.0, 0.0),
+        false => Vec3::new(1.0, 0.0, 0.0),
+    };
+    let s: Vec3 = unit_vector(cross(axis, a));
+    let t: Vec3 = cross(axis, s);
+    sin_theta * phi.cos() * s + sin_theta * phi.sin() * t + cos_theta * axis
+}
+
 pub fn reflect(v: &Vec3, n: &Vec3) -> Vec3 {
     *v - 2.0 * dot(*v, *n) * *n
 }